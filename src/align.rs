@@ -0,0 +1,200 @@
+use core::{mem, slice};
+
+#[cfg(feature = "portable_simd")]
+use core::simd::prelude::*;
+
+/// A type that a byte slice can be viewed as a sequence of, as the batches of
+/// [`AlignedChunks`](struct.AlignedChunks.html).
+///
+/// # Safety
+///
+/// Every sequence of `mem::size_of::<Self>()` bytes must be a valid value of
+/// `Self`, and every value of `Self` must have no padding bytes. This holds
+/// for the primitive integers, byte arrays and vectors of bytes.
+pub unsafe trait Chunk: Copy {}
+
+macro_rules! impl_chunk {
+    ($($t:ty)+) => { $(
+        unsafe impl Chunk for $t {}
+    )+ }
+}
+
+impl_chunk! { u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize }
+
+#[cfg(feature = "portable_simd")]
+impl_chunk! { u8x2 u8x4 u8x8 u8x16 u8x32 u8x64 }
+
+unsafe impl<const N: usize> Chunk for [u8; N] {}
+
+/// A byte slice split around its longest run of whole batches of `B` that are
+/// aligned in memory.
+///
+/// If `B` is zero-sized, every byte is left in the prefix.
+///
+/// This is how the crate itself walks slices: the short `prefix` and `suffix`
+/// are handled a byte at a time and the `batches` in between with aligned
+/// loads. Custom per-batch predicates can be written the same way:
+///
+/// ```
+/// use byte_ops::AlignedChunks;
+///
+/// // Returns whether any byte in `bytes` has its high bit set
+/// fn any_high(bytes: &[u8]) -> bool {
+///     let chunks = AlignedChunks::<u64>::new(bytes);
+///     chunks.prefix.iter().chain(chunks.suffix).any(|&b| b >= 0x80) ||
+///     chunks.batches.iter().any(|&x| x & 0x8080_8080_8080_8080 != 0)
+/// }
+///
+/// assert!(any_high("naïve".as_bytes()));
+/// assert!(!any_high(&[b'a'; 100][3..]));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct AlignedChunks<'a, B: 'a> {
+    /// The bytes before the first aligned address, fewer than the alignment
+    /// of `B`.
+    pub prefix: &'a [u8],
+    /// The aligned batches, which may be empty if the slice is too short.
+    pub batches: &'a [B],
+    /// The bytes after the last batch, fewer than make up a batch.
+    pub suffix: &'a [u8],
+}
+
+/// The mutable counterpart to [`AlignedChunks`](struct.AlignedChunks.html).
+#[derive(Debug)]
+pub struct AlignedChunksMut<'a, B: 'a> {
+    /// The bytes before the first aligned address, fewer than the alignment
+    /// of `B`.
+    pub prefix: &'a mut [u8],
+    /// The aligned batches, which may be empty if the slice is too short.
+    pub batches: &'a mut [B],
+    /// The bytes after the last batch, fewer than make up a batch.
+    pub suffix: &'a mut [u8],
+}
+
+// Returns the lengths of the prefix and of the batches of `B` when splitting
+// the `len` bytes at `address`.
+//
+// Based on the alignment code used by the `bytecount` crate.
+#[inline]
+fn split<B>(address: usize, len: usize) -> (usize, usize) {
+    let size = mem::size_of::<B>();
+    let align = mem::align_of::<B>();
+
+    let prefix = ((align - address % align) % align).min(len);
+    match size {
+        0 => (len, 0),
+        _ => (prefix, (len - prefix) / size),
+    }
+}
+
+impl<'a, B: Chunk> AlignedChunks<'a, B> {
+    /// Splits `bytes` into an unaligned prefix, aligned batches of `B` and an
+    /// unaligned suffix.
+    #[inline]
+    pub fn new(bytes: &'a [u8]) -> Self {
+        let (prefix, n) = split::<B>(bytes.as_ptr() as usize, bytes.len());
+        let (prefix, rest) = bytes.split_at(prefix);
+        let (mid, suffix) = rest.split_at(n * mem::size_of::<B>());
+
+        // A short slice leaves an empty `mid`, which may be unaligned
+        let batches = match n {
+            0 => &[],
+            _ => unsafe { slice::from_raw_parts(mid.as_ptr() as *const B, n) },
+        };
+        AlignedChunks { prefix, batches, suffix }
+    }
+
+    /// Returns whether any batch satisfies `batch` or any byte of the prefix
+    /// or suffix satisfies `byte`.
+    ///
+    /// The two predicates should agree: `batch` returns whether any byte of a
+    /// batch would satisfy `byte`.
+    #[inline]
+    pub fn any_batch<F, G>(&self, batch: F, byte: G) -> bool
+        where F: FnMut(B) -> bool, G: FnMut(u8) -> bool
+    {
+        let mut byte = byte;
+        self.batches.iter().cloned().any(batch) ||
+        self.prefix.iter().chain(self.suffix).any(|&b| byte(b))
+    }
+
+    /// Returns whether every batch satisfies `batch` and every byte of the
+    /// prefix and suffix satisfies `byte`.
+    ///
+    /// The two predicates should agree: `batch` returns whether every byte of
+    /// a batch would satisfy `byte`.
+    #[inline]
+    pub fn all_batch<F, G>(&self, batch: F, byte: G) -> bool
+        where F: FnMut(B) -> bool, G: FnMut(u8) -> bool
+    {
+        let mut byte = byte;
+        self.batches.iter().cloned().all(batch) &&
+        self.prefix.iter().chain(self.suffix).all(|&b| byte(b))
+    }
+
+    /// Returns the index of the first byte that satisfies `byte`, testing the
+    /// batches with `batch` instead.
+    ///
+    /// The two predicates should agree: `batch` returns the index within a
+    /// batch of its first byte that would satisfy `byte`, so a matching batch
+    /// is never scanned again a byte at a time.
+    ///
+    /// ```
+    /// use byte_ops::AlignedChunks;
+    ///
+    /// const HI: u64 = 0x8080_8080_8080_8080;
+    ///
+    /// // Reading the batch as little-endian puts its first byte lowest
+    /// fn first_high(x: u64) -> Option<usize> {
+    ///     let mask = u64::from_le(x) & HI;
+    ///     match mask {
+    ///         0 => None,
+    ///         _ => Some(mask.trailing_zeros() as usize / 8),
+    ///     }
+    /// }
+    ///
+    /// let text = "plain ASCII text, then some ☃ and more".as_bytes();
+    /// let chunks = AlignedChunks::<u64>::new(text);
+    /// let first = chunks.first_where(first_high, |b| b >= 0x80);
+    /// assert_eq!(first, text.iter().position(|&b| b >= 0x80));
+    /// ```
+    #[inline]
+    pub fn first_where<F, G>(&self, batch: F, byte: G) -> Option<usize>
+        where F: FnMut(B) -> Option<usize>, G: FnMut(u8) -> bool
+    {
+        let (mut batch, mut byte) = (batch, byte);
+        let size = mem::size_of::<B>();
+
+        if let Some(i) = self.prefix.iter().position(|&b| byte(b)) {
+            return Some(i);
+        }
+        let mut start = self.prefix.len();
+        for x in self.batches {
+            if let Some(i) = batch(*x) {
+                return Some(start + i);
+            }
+            start += size;
+        }
+        let i = self.suffix.iter().position(|&b| byte(b))?;
+        Some(start + i)
+    }
+}
+
+impl<'a, B: Chunk> AlignedChunksMut<'a, B> {
+    /// Splits `bytes` into an unaligned prefix, aligned batches of `B` and an
+    /// unaligned suffix, all of which can be written to.
+    #[inline]
+    pub fn new(bytes: &'a mut [u8]) -> Self {
+        let (prefix, n) = split::<B>(bytes.as_ptr() as usize, bytes.len());
+        let (prefix, rest) = bytes.split_at_mut(prefix);
+        let (mid, suffix) = rest.split_at_mut(n * mem::size_of::<B>());
+
+        let batches = match n {
+            0 => &mut [],
+            _ => unsafe {
+                slice::from_raw_parts_mut(mid.as_mut_ptr() as *mut B, n)
+            },
+        };
+        AlignedChunksMut { prefix, batches, suffix }
+    }
+}
@@ -0,0 +1,394 @@
+use core::{mem, ptr};
+use core::ops::Range;
+
+use super::*;
+
+// A byte predicate that can also be tested on a whole batch at once.
+trait Pred: Copy {
+    fn test(self, byte: u8) -> bool;
+
+    fn bits<T: Matches>(self, batch: T) -> u64;
+}
+
+#[derive(Clone, Copy)]
+struct IsEq(u8);
+
+#[derive(Clone, Copy)]
+struct IsNe(u8);
+
+#[derive(Clone, Copy)]
+struct IsAny2(u8, u8);
+
+#[derive(Clone, Copy)]
+struct IsAny3(u8, u8, u8);
+
+#[derive(Clone, Copy)]
+struct IsIn<'a>(&'a ByteSet);
+
+#[derive(Clone, Copy)]
+struct IsInRange(ByteRange);
+
+#[derive(Clone, Copy)]
+struct IsHigh;
+
+impl Pred for IsEq {
+    #[inline]
+    fn test(self, byte: u8) -> bool { byte == self.0 }
+
+    #[inline]
+    fn bits<T: Matches>(self, batch: T) -> u64 { batch.eq_bits(self.0) }
+}
+
+impl Pred for IsNe {
+    #[inline]
+    fn test(self, byte: u8) -> bool { byte != self.0 }
+
+    #[inline]
+    fn bits<T: Matches>(self, batch: T) -> u64 { batch.ne_bits(self.0) }
+}
+
+impl Pred for IsAny2 {
+    #[inline]
+    fn test(self, byte: u8) -> bool { byte == self.0 || byte == self.1 }
+
+    #[inline]
+    fn bits<T: Matches>(self, batch: T) -> u64 {
+        batch.eq_bits(self.0) | batch.eq_bits(self.1)
+    }
+}
+
+impl Pred for IsAny3 {
+    #[inline]
+    fn test(self, byte: u8) -> bool {
+        byte == self.0 || byte == self.1 || byte == self.2
+    }
+
+    #[inline]
+    fn bits<T: Matches>(self, batch: T) -> u64 {
+        batch.eq_bits(self.0) | batch.eq_bits(self.1) | batch.eq_bits(self.2)
+    }
+}
+
+impl<'a> Pred for IsIn<'a> {
+    #[inline]
+    fn test(self, byte: u8) -> bool { self.0.contains(byte) }
+
+    #[inline]
+    fn bits<T: Matches>(self, batch: T) -> u64 { batch.in_bits(self.0) }
+}
+
+impl Pred for IsInRange {
+    #[inline]
+    fn test(self, byte: u8) -> bool { self.0.contains(byte) }
+
+    #[inline]
+    fn bits<T: Matches>(self, batch: T) -> u64 { batch.range_bits(self.0) }
+}
+
+impl Pred for IsHigh {
+    #[inline]
+    fn test(self, byte: u8) -> bool { byte >= 0x80 }
+
+    #[inline]
+    fn bits<T: Matches>(self, batch: T) -> u64 { batch.high_bits() }
+}
+
+// The ranges of an `N`-byte array that are read as whole batches, words,
+// `u32`s and `u16`s, in that order, followed by at most one byte.
+//
+// Everything here is a constant once `N` is known, so the loops over these
+// ranges fully unroll for small arrays.
+#[inline(always)]
+fn parts<const N: usize>() -> [Range<usize>; 5] {
+    fn next<T>(start: usize, n: usize) -> Range<usize> {
+        let size = mem::size_of::<T>();
+        start..start + (n - start) / size * size
+    }
+
+    let batches = next::<Batch>(0, N);
+    let words = next::<usize>(batches.end, N);
+    let halves = next::<u32>(words.end, N);
+    let quarters = next::<u16>(halves.end, N);
+    let bytes = quarters.end..N;
+    [batches, words, halves, quarters, bytes]
+}
+
+// Reads the `T` starting at `arr[i]`, which may be unaligned.
+#[inline(always)]
+unsafe fn load<T, const N: usize>(arr: &[u8; N], i: usize) -> T {
+    debug_assert!(i + mem::size_of::<T>() <= N);
+    ptr::read_unaligned(arr.as_ptr().add(i) as *const T)
+}
+
+// Returns the offset of the first byte in `arr[r]` that satisfies `p`,
+// testing a whole `T` at a time.
+#[inline(always)]
+fn first_part<T, P, const N: usize>(arr: &[u8; N], r: Range<usize>, p: P)
+    -> Option<usize>
+    where T: Matches, P: Pred
+{
+    let mut i = r.start;
+    while i < r.end {
+        let batch = unsafe { load::<T, N>(arr, i) };
+        if let Some(pos) = first_pos::<T>(p.bits(batch)) {
+            return Some(i + pos);
+        }
+        i += mem::size_of::<T>();
+    }
+    None
+}
+
+// Returns the offset of the last byte in `arr[r]` that satisfies `p`,
+// testing a whole `T` at a time.
+#[inline(always)]
+fn last_part<T, P, const N: usize>(arr: &[u8; N], r: Range<usize>, p: P)
+    -> Option<usize>
+    where T: Matches, P: Pred
+{
+    let mut i = r.end;
+    while i > r.start {
+        i -= mem::size_of::<T>();
+        let batch = unsafe { load::<T, N>(arr, i) };
+        if let Some(pos) = last_pos::<T>(p.bits(batch)) {
+            return Some(i + pos);
+        }
+    }
+    None
+}
+
+// Returns the number of bytes in `arr[r]` that satisfy `p`, testing a whole
+// `T` at a time.
+#[inline(always)]
+fn count_part<T, P, const N: usize>(arr: &[u8; N], r: Range<usize>, p: P)
+    -> usize
+    where T: Matches, P: Pred
+{
+    let mut count = 0;
+    let mut i = r.start;
+    while i < r.end {
+        let batch = unsafe { load::<T, N>(arr, i) };
+        count += p.bits(batch).count_ones() as usize;
+        i += mem::size_of::<T>();
+    }
+    count
+}
+
+#[inline]
+fn first<P: Pred, const N: usize>(arr: &[u8; N], p: P) -> Option<usize> {
+    let [batches, words, halves, quarters, bytes] = parts::<N>();
+
+    if let Some(i) = first_part::<Batch, _, N>(arr, batches, p) {
+        return Some(i);
+    }
+    if let Some(i) = first_part::<usize, _, N>(arr, words, p) {
+        return Some(i);
+    }
+    if let Some(i) = first_part::<u32, _, N>(arr, halves, p) {
+        return Some(i);
+    }
+    if let Some(i) = first_part::<u16, _, N>(arr, quarters, p) {
+        return Some(i);
+    }
+    bytes.clone().find(|&i| p.test(arr[i]))
+}
+
+#[inline]
+fn last<P: Pred, const N: usize>(arr: &[u8; N], p: P) -> Option<usize> {
+    let [batches, words, halves, quarters, bytes] = parts::<N>();
+
+    if let Some(i) = bytes.clone().rev().find(|&i| p.test(arr[i])) {
+        return Some(i);
+    }
+    if let Some(i) = last_part::<u16, _, N>(arr, quarters, p) {
+        return Some(i);
+    }
+    if let Some(i) = last_part::<u32, _, N>(arr, halves, p) {
+        return Some(i);
+    }
+    if let Some(i) = last_part::<usize, _, N>(arr, words, p) {
+        return Some(i);
+    }
+    last_part::<Batch, _, N>(arr, batches, p)
+}
+
+#[inline]
+fn count<P: Pred, const N: usize>(arr: &[u8; N], p: P) -> usize {
+    let [batches, words, halves, quarters, bytes] = parts::<N>();
+
+    count_part::<Batch, _, N>(arr, batches, p) +
+    count_part::<usize, _, N>(arr, words, p) +
+    count_part::<u32, _, N>(arr, halves, p) +
+    count_part::<u16, _, N>(arr, quarters, p) +
+    bytes.filter(|&i| p.test(arr[i])).count()
+}
+
+impl<const N: usize> Bytes for [u8; N] {
+    #[inline]
+    fn is(&self, byte: u8) -> bool {
+        N != 0 && first(self, IsNe(byte)).is_none()
+    }
+
+    #[inline]
+    fn contains(&self, byte: u8) -> bool {
+        first(self, IsEq(byte)).is_some()
+    }
+
+    #[inline]
+    fn first_eq(&self, byte: u8) -> Option<&u8> {
+        let i = self.position_eq(byte)?;
+        Some(unsafe { self.get_unchecked(i) })
+    }
+
+    #[inline]
+    fn first_ne(&self, byte: u8) -> Option<&u8> {
+        let i = self.position_ne(byte)?;
+        Some(unsafe { self.get_unchecked(i) })
+    }
+
+    #[inline]
+    fn last_eq(&self, byte: u8) -> Option<&u8> {
+        let i = self.rposition_eq(byte)?;
+        Some(unsafe { self.get_unchecked(i) })
+    }
+
+    #[inline]
+    fn last_ne(&self, byte: u8) -> Option<&u8> {
+        let i = self.rposition_ne(byte)?;
+        Some(unsafe { self.get_unchecked(i) })
+    }
+
+    #[inline]
+    fn position_eq(&self, byte: u8) -> Option<usize> {
+        first(self, IsEq(byte))
+    }
+
+    #[inline]
+    fn position_ne(&self, byte: u8) -> Option<usize> {
+        first(self, IsNe(byte))
+    }
+
+    #[inline]
+    fn rposition_eq(&self, byte: u8) -> Option<usize> {
+        last(self, IsEq(byte))
+    }
+
+    #[inline]
+    fn rposition_ne(&self, byte: u8) -> Option<usize> {
+        last(self, IsNe(byte))
+    }
+
+    #[inline]
+    fn first_any2(&self, a: u8, b: u8) -> Option<&u8> {
+        let i = first(self, IsAny2(a, b))?;
+        Some(unsafe { self.get_unchecked(i) })
+    }
+
+    #[inline]
+    fn first_any3(&self, a: u8, b: u8, c: u8) -> Option<&u8> {
+        let i = first(self, IsAny3(a, b, c))?;
+        Some(unsafe { self.get_unchecked(i) })
+    }
+
+    #[inline]
+    fn all_in(&self, set: &ByteSet) -> bool {
+        N != 0 && first(self, IsIn(&set.complement())).is_none()
+    }
+
+    #[inline]
+    fn first_in(&self, set: &ByteSet) -> Option<&u8> {
+        let i = first(self, IsIn(set))?;
+        Some(unsafe { self.get_unchecked(i) })
+    }
+
+    #[inline]
+    fn first_non_ascii(&self) -> Option<&u8> {
+        let i = first(self, IsHigh)?;
+        Some(unsafe { self.get_unchecked(i) })
+    }
+
+    #[inline]
+    fn all_in_range(&self, range: RangeInclusive<u8>) -> bool {
+        N != 0 && all_in_range(range, |r| first(self, IsInRange(r)).is_some())
+    }
+
+    #[inline]
+    fn first_in_range(&self, range: RangeInclusive<u8>) -> Option<&u8> {
+        let i = first(self, IsInRange(ByteRange::new(range)?))?;
+        Some(unsafe { self.get_unchecked(i) })
+    }
+
+    #[inline]
+    fn count_eq(&self, byte: u8) -> usize {
+        count(self, IsEq(byte))
+    }
+
+    #[inline]
+    fn count_ne(&self, byte: u8) -> usize {
+        count(self, IsNe(byte))
+    }
+
+    #[inline]
+    fn iter_eq(&self, byte: u8) -> Positions<'_> {
+        Positions::new(self, byte, true)
+    }
+
+    #[inline]
+    fn iter_ne(&self, byte: u8) -> Positions<'_> {
+        Positions::new(self, byte, false)
+    }
+
+    #[inline]
+    fn find(&self, needle: &[u8]) -> Option<usize> {
+        search::find(self, needle)
+    }
+
+    #[inline]
+    fn rfind(&self, needle: &[u8]) -> Option<usize> {
+        search::rfind(self, needle)
+    }
+}
+
+impl<const N: usize> BytesMut for [u8; N] {
+    #[inline]
+    fn first_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
+        let i = self.position_eq(byte)?;
+        Some(unsafe { self.get_unchecked_mut(i) })
+    }
+
+    #[inline]
+    fn first_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
+        let i = self.position_ne(byte)?;
+        Some(unsafe { self.get_unchecked_mut(i) })
+    }
+
+    #[inline]
+    fn last_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
+        let i = self.rposition_eq(byte)?;
+        Some(unsafe { self.get_unchecked_mut(i) })
+    }
+
+    #[inline]
+    fn last_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
+        let i = self.rposition_ne(byte)?;
+        Some(unsafe { self.get_unchecked_mut(i) })
+    }
+
+    // Arrays and slices have an inherent `fill`
+    #[inline]
+    fn fill(&mut self, byte: u8) { BytesMut::fill(&mut self[..], byte) }
+
+    #[inline]
+    fn replace(&mut self, from: u8, to: u8) {
+        BytesMut::replace(&mut self[..], from, to)
+    }
+
+    #[inline]
+    fn replace_all_in(&mut self, set: &ByteSet, to: u8) {
+        BytesMut::replace_all_in(&mut self[..], set, to)
+    }
+}
+
+impl<const N: usize> SizedBytes for [u8; N] {
+    #[inline]
+    fn splat(byte: u8) -> Self { [byte; N] }
+}
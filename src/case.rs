@@ -0,0 +1,83 @@
+use core::{mem, ptr};
+
+use super::*;
+
+/// ASCII case conversion and comparison of bytes, done a whole batch at a
+/// time.
+///
+/// These match the standard library's methods of the same names. Since
+/// slices have those as inherent methods, calls on a slice must be written
+/// as `AsciiCase::make_ascii_lowercase(slice)` to use these instead.
+pub trait AsciiCase {
+    /// Converts every ASCII uppercase letter in `self` to lowercase.
+    fn make_ascii_lowercase(&mut self);
+
+    /// Converts every ASCII lowercase letter in `self` to uppercase.
+    fn make_ascii_uppercase(&mut self);
+
+    /// Returns whether `self` and `other` are equal, ignoring the ASCII case
+    /// of letters.
+    fn eq_ignore_ascii_case(&self, other: &[u8]) -> bool;
+}
+
+const UPPER: ByteRange = ByteRange { lo: b'A', span: b'Z' - b'A' };
+const LOWER: ByteRange = ByteRange { lo: b'a', span: b'z' - b'a' };
+
+// The bit that differs between the cases of an ASCII letter
+const CASE: u8 = 0x20;
+
+// Flips the case of every letter of `bytes` in `range`.
+#[inline]
+fn flip_case(bytes: &mut [u8], range: ByteRange) {
+    rewrite(bytes, |x| x.flip_in_range(range, CASE), |b| {
+        if range.contains(b) { b ^ CASE } else { b }
+    });
+}
+
+impl AsciiCase for [u8] {
+    #[inline]
+    fn make_ascii_lowercase(&mut self) { flip_case(self, UPPER) }
+
+    #[inline]
+    fn make_ascii_uppercase(&mut self) { flip_case(self, LOWER) }
+
+    // The two slices are rarely aligned the same way, so their batches are
+    // read unaligned
+    fn eq_ignore_ascii_case(&self, other: &[u8]) -> bool {
+        const SIZE: usize = mem::size_of::<Batch>();
+
+        if self.len() != other.len() {
+            return false;
+        }
+
+        let lower = |b: &[u8]| {
+            let ptr = b.as_ptr() as *const Batch;
+            unsafe { ptr::read_unaligned(ptr) }.flip_in_range(UPPER, CASE)
+        };
+
+        let mut a = self.chunks_exact(SIZE);
+        let mut b = other.chunks_exact(SIZE);
+        if !a.by_ref().zip(b.by_ref()).all(|(x, y)| lower(x) == lower(y)) {
+            return false;
+        }
+        let (a, b) = (a.remainder(), b.remainder());
+        a.iter().zip(b).all(|(x, y)| x.eq_ignore_ascii_case(y))
+    }
+}
+
+impl<const N: usize> AsciiCase for [u8; N] {
+    #[inline]
+    fn make_ascii_lowercase(&mut self) {
+        AsciiCase::make_ascii_lowercase(&mut self[..])
+    }
+
+    #[inline]
+    fn make_ascii_uppercase(&mut self) {
+        AsciiCase::make_ascii_uppercase(&mut self[..])
+    }
+
+    #[inline]
+    fn eq_ignore_ascii_case(&self, other: &[u8]) -> bool {
+        AsciiCase::eq_ignore_ascii_case(&self[..], other)
+    }
+}
@@ -0,0 +1,145 @@
+use core::mem;
+
+#[cfg(feature = "portable_simd")]
+use super::*;
+
+/// A type that can be treated as a sequence of `T`s, for searching integers
+/// wider than a byte, such as UTF-16 code units.
+///
+/// This is the counterpart to [`Bytes`](trait.Bytes.html) for `[u16]`,
+/// `[u32]` and `[u64]`, which are searched a whole batch of elements at a
+/// time. Since slices have an inherent `contains` method, it must be called
+/// as `Elements::contains(slice, elem)`.
+pub trait Elements<T> {
+    /// Returns whether every element in `self` is `elem`.
+    fn is(&self, elem: T) -> bool;
+
+    /// Returns whether `self` contains `elem`.
+    fn contains(&self, elem: T) -> bool;
+
+    /// Returns a reference to the first element in `self` that equals `elem`.
+    fn first_eq(&self, elem: T) -> Option<&T>;
+
+    /// Returns the number of elements in `self` that equal `elem`.
+    fn count_eq(&self, elem: T) -> usize;
+}
+
+// The batches elements are tested in: a word wide enough for a `u64`, or a
+// vector split into wider lanes.
+#[cfg(not(feature = "portable_simd"))]
+type Lanes = u64;
+
+#[cfg(feature = "portable_simd")]
+type Lanes = Batch;
+
+// An element whose lanes within a batch can be compared all at once.
+//
+// Bit `i * STRIDE` of a mask corresponds to the element at index `i` of the
+// batch in memory, as with `Matches`.
+trait Lane: Copy + Eq {
+    // The number of mask bits per element.
+    const STRIDE: u32;
+
+    // The number of elements per batch.
+    const COUNT: usize = mem::size_of::<Lanes>() / mem::size_of::<Self>();
+
+    // Returns a mask with the bits of each element equal to `elem` set.
+    fn eq_bits(batch: Lanes, elem: Self) -> u64;
+}
+
+// Implementations for each element type wider than a byte
+macro_rules! impl_elements {
+    ($($t:ident,)+) => { $(
+        #[cfg(not(feature = "portable_simd"))]
+        impl Lane for $t {
+            const STRIDE: u32 = $t::BITS;
+
+            // The same zero test as `Matches::eq_bits` on bytes, only with
+            // each lane's high bit a whole element apart
+            #[inline]
+            fn eq_bits(batch: u64, elem: $t) -> u64 {
+                const LO: u64 = u64::MAX / $t::MAX as u64;
+                const LOW: u64 = !(LO << ($t::BITS - 1));
+
+                let x = u64::from_le(batch) ^ LO * elem.to_le() as u64;
+                !((x & LOW) + LOW | x | LOW)
+            }
+        }
+
+        #[cfg(feature = "portable_simd")]
+        impl Lane for $t {
+            const STRIDE: u32 = 1;
+
+            #[inline]
+            fn eq_bits(batch: Batch, elem: $t) -> u64 {
+                const N: usize = mem::size_of::<Batch>() / mem::size_of::<$t>();
+                type V = Simd<$t, N>;
+
+                let v: V = unsafe { mem::transmute(batch) };
+                v.simd_eq(V::splat(elem)).to_bitmask()
+            }
+        }
+
+        impl Elements<$t> for [$t] {
+            #[inline]
+            fn is(&self, elem: $t) -> bool { all(self, elem) }
+
+            #[inline]
+            fn contains(&self, elem: $t) -> bool {
+                position(self, elem).is_some()
+            }
+
+            #[inline]
+            fn first_eq(&self, elem: $t) -> Option<&$t> {
+                let i = position(self, elem)?;
+                Some(unsafe { self.get_unchecked(i) })
+            }
+
+            #[inline]
+            fn count_eq(&self, elem: $t) -> usize { count(self, elem) }
+        }
+    )+ }
+}
+
+// Returns the index of the first element in `s` equal to `elem`, testing the
+// aligned middle a whole batch at a time.
+#[inline]
+fn position<T: Lane>(s: &[T], elem: T) -> Option<usize> {
+    let (x, y, z) = unsafe { s.align_to::<Lanes>() };
+
+    if let Some(i) = x.iter().position(|&e| e == elem) {
+        return Some(i);
+    }
+    for (i, &batch) in y.iter().enumerate() {
+        let mask = T::eq_bits(batch, elem);
+        if mask != 0 {
+            let pos = (mask.trailing_zeros() / T::STRIDE) as usize;
+            return Some(x.len() + i * T::COUNT + pos);
+        }
+    }
+    z.iter().position(|&e| e == elem).map(|i| s.len() - z.len() + i)
+}
+
+// Returns whether `s` is nonempty and every element in it equals `elem`.
+#[inline]
+fn all<T: Lane>(s: &[T], elem: T) -> bool {
+    let (x, y, z) = unsafe { s.align_to::<Lanes>() };
+
+    !s.is_empty() &&
+    x.iter().chain(z).all(|&e| e == elem) &&
+    y.iter().all(|&b| T::eq_bits(b, elem).count_ones() as usize == T::COUNT)
+}
+
+// Returns the number of elements in `s` equal to `elem`.
+#[inline]
+fn count<T: Lane>(s: &[T], elem: T) -> usize {
+    let (x, y, z) = unsafe { s.align_to::<Lanes>() };
+
+    let ends = x.iter().chain(z).filter(|&&e| e == elem).count();
+    let mid: usize = y.iter().map(|&b| {
+        T::eq_bits(b, elem).count_ones() as usize
+    }).sum();
+    ends + mid
+}
+
+impl_elements! { u16, u32, u64, }
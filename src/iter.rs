@@ -0,0 +1,131 @@
+use core::iter::FusedIterator;
+use core::mem;
+
+use super::*;
+
+/// An iterator over the indices of the bytes that are [not] equal to a byte.
+///
+/// This is created by [`Bytes::iter_eq`](trait.Bytes.html#tymethod.iter_eq)
+/// and [`Bytes::iter_ne`](trait.Bytes.html#tymethod.iter_ne).
+#[derive(Clone, Debug)]
+pub struct Positions<'a> {
+    byte: u8,
+    eq: bool,
+    // Remaining marks within the batch last taken from the front
+    front: u64,
+    front_start: usize,
+    // Remaining marks within the batch last taken from the back
+    back: u64,
+    back_start: usize,
+    // The batches not yet taken from either end
+    mid: &'a [Batch],
+    mid_start: usize,
+}
+
+impl<'a> Positions<'a> {
+    #[inline]
+    pub(crate) fn new(bytes: &'a [u8], byte: u8, eq: bool) -> Self {
+        let (x, y, z) = batch_align(bytes);
+
+        // The unaligned ends are shorter than a batch, so their masks can be
+        // laid out the same way
+        let mask = |slice: &[u8]| {
+            slice.iter().enumerate().fold(0, |mask, (i, &b)| {
+                let bit = ((b == byte) == eq) as u64;
+                mask | bit << (i as u32 * Batch::STRIDE)
+            })
+        };
+
+        Positions {
+            byte,
+            eq,
+            front: mask(x),
+            front_start: 0,
+            back: mask(z),
+            back_start: bytes.len() - z.len(),
+            mid: y,
+            mid_start: x.len(),
+        }
+    }
+
+    #[inline]
+    fn mask(&self, batch: Batch) -> u64 {
+        if self.eq {
+            batch.eq_bits(self.byte)
+        } else {
+            batch.ne_bits(self.byte)
+        }
+    }
+}
+
+// Removes the lowest mark in `mask` and returns its offset.
+#[inline]
+fn pop_first(mask: &mut u64) -> usize {
+    let pos = (mask.trailing_zeros() / Batch::STRIDE) as usize;
+    *mask &= *mask - 1;
+    pos
+}
+
+// Removes the highest mark in `mask` and returns its offset.
+#[inline]
+fn pop_last(mask: &mut u64) -> usize {
+    let bit = 63 - mask.leading_zeros();
+    *mask &= !(1 << bit);
+    (bit / Batch::STRIDE) as usize
+}
+
+impl<'a> Iterator for Positions<'a> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        loop {
+            if self.front != 0 {
+                return Some(self.front_start + pop_first(&mut self.front));
+            }
+            match self.mid.split_first() {
+                Some((&batch, rest)) => {
+                    self.front = self.mask(batch);
+                    self.front_start = self.mid_start;
+                    self.mid_start += mem::size_of::<Batch>();
+                    self.mid = rest;
+                },
+                None if self.back != 0 => {
+                    return Some(self.back_start + pop_first(&mut self.back));
+                },
+                None => return None,
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let ends = (self.front.count_ones() + self.back.count_ones()) as usize;
+        let mid = mem::size_of_val(self.mid);
+        (ends, Some(ends + mid))
+    }
+}
+
+impl<'a> DoubleEndedIterator for Positions<'a> {
+    #[inline]
+    fn next_back(&mut self) -> Option<usize> {
+        loop {
+            if self.back != 0 {
+                return Some(self.back_start + pop_last(&mut self.back));
+            }
+            match self.mid.split_last() {
+                Some((&batch, rest)) => {
+                    self.back = self.mask(batch);
+                    self.back_start = self.mid_start + mem::size_of_val(rest);
+                    self.mid = rest;
+                },
+                None if self.front != 0 => {
+                    return Some(self.front_start + pop_last(&mut self.front));
+                },
+                None => return None,
+            }
+        }
+    }
+}
+
+impl<'a> FusedIterator for Positions<'a> {}
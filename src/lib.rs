@@ -1,13 +1,18 @@
 //! Perform common byte operations on arrays and slices, quickly.
 
 #![cfg_attr(not(feature = "std"), no_std)]
-#![cfg_attr(feature = "simd", feature(stdsimd))]
+#![cfg_attr(feature = "portable_simd", feature(portable_simd))]
 
 #![deny(missing_docs)]
 
 #[cfg(test)]
 extern crate rand;
 
+// Tests allocate even without the `std` feature
+#[cfg(all(test, not(feature = "std")))]
+#[macro_use]
+extern crate std;
+
 #[cfg(test)]
 #[macro_use]
 extern crate static_assertions;
@@ -15,24 +20,334 @@ extern crate static_assertions;
 #[cfg(feature = "std")]
 use std as core;
 
-#[cfg(feature = "simd")]
-use core::simd::*;
+use core::ops::RangeInclusive;
+
+#[cfg(feature = "portable_simd")]
+use core::simd::prelude::*;
+
+// Returns the result of `$f` from the kernels `$k` of the x86 backend selected
+// at runtime, if the current CPU has one.
+macro_rules! with_x86 {
+    (|$k:ident| $f:expr) => {
+        #[cfg(all(feature = "std",
+                  any(target_arch = "x86", target_arch = "x86_64")))]
+        {
+            if let Some($k) = x86::kernels() {
+                return unsafe { $f };
+            }
+        }
+    };
+}
+
+// Implements `Bytes` for each `$t` by forwarding to `$e`, which views `$x`
+// (`self`) as another implementor.
+macro_rules! impl_bytes_via {
+    ($($t:ty => |$x:ident| $e:expr,)+) => { $(
+        impl_bytes_via!(@impl $t, $x, $e);
+    )+ };
+    (@impl $t:ty, $x:ident, $e:expr) => {
+        impl Bytes for $t {
+            #[inline]
+            fn is(&self, byte: u8) -> bool {
+                let $x = self;
+                Bytes::is($e, byte)
+            }
+
+            #[inline]
+            fn is_zero(&self) -> bool {
+                let $x = self;
+                Bytes::is_zero($e)
+            }
+
+            #[inline]
+            fn contains(&self, byte: u8) -> bool {
+                let $x = self;
+                Bytes::contains($e, byte)
+            }
+
+            #[inline]
+            fn contains_zero(&self) -> bool {
+                let $x = self;
+                Bytes::contains_zero($e)
+            }
+
+            #[inline]
+            fn contains_any2(&self, a: u8, b: u8) -> bool {
+                let $x = self;
+                Bytes::contains_any2($e, a, b)
+            }
+
+            #[inline]
+            fn contains_any3(&self, a: u8, b: u8, c: u8) -> bool {
+                let $x = self;
+                Bytes::contains_any3($e, a, b, c)
+            }
+
+            #[inline]
+            fn first_eq(&self, byte: u8) -> Option<&u8> {
+                let $x = self;
+                Bytes::first_eq($e, byte)
+            }
+
+            #[inline]
+            fn first_ne(&self, byte: u8) -> Option<&u8> {
+                let $x = self;
+                Bytes::first_ne($e, byte)
+            }
+
+            #[inline]
+            fn last_eq(&self, byte: u8) -> Option<&u8> {
+                let $x = self;
+                Bytes::last_eq($e, byte)
+            }
+
+            #[inline]
+            fn last_ne(&self, byte: u8) -> Option<&u8> {
+                let $x = self;
+                Bytes::last_ne($e, byte)
+            }
+
+            #[inline]
+            fn position_eq(&self, byte: u8) -> Option<usize> {
+                let $x = self;
+                Bytes::position_eq($e, byte)
+            }
+
+            #[inline]
+            fn position_ne(&self, byte: u8) -> Option<usize> {
+                let $x = self;
+                Bytes::position_ne($e, byte)
+            }
+
+            #[inline]
+            fn rposition_eq(&self, byte: u8) -> Option<usize> {
+                let $x = self;
+                Bytes::rposition_eq($e, byte)
+            }
+
+            #[inline]
+            fn rposition_ne(&self, byte: u8) -> Option<usize> {
+                let $x = self;
+                Bytes::rposition_ne($e, byte)
+            }
+
+            #[inline]
+            fn first_any2(&self, a: u8, b: u8) -> Option<&u8> {
+                let $x = self;
+                Bytes::first_any2($e, a, b)
+            }
+
+            #[inline]
+            fn first_any3(&self, a: u8, b: u8, c: u8) -> Option<&u8> {
+                let $x = self;
+                Bytes::first_any3($e, a, b, c)
+            }
+
+            #[inline]
+            fn contains_in(&self, set: &ByteSet) -> bool {
+                let $x = self;
+                Bytes::contains_in($e, set)
+            }
+
+            #[inline]
+            fn all_in(&self, set: &ByteSet) -> bool {
+                let $x = self;
+                Bytes::all_in($e, set)
+            }
+
+            #[inline]
+            fn first_in(&self, set: &ByteSet) -> Option<&u8> {
+                let $x = self;
+                Bytes::first_in($e, set)
+            }
+
+            #[inline]
+            fn is_ascii(&self) -> bool {
+                let $x = self;
+                Bytes::is_ascii($e)
+            }
+
+            #[inline]
+            fn first_non_ascii(&self) -> Option<&u8> {
+                let $x = self;
+                Bytes::first_non_ascii($e)
+            }
+
+            #[inline]
+            fn contains_in_range(&self, range: RangeInclusive<u8>) -> bool {
+                let $x = self;
+                Bytes::contains_in_range($e, range)
+            }
+
+            #[inline]
+            fn all_in_range(&self, range: RangeInclusive<u8>) -> bool {
+                let $x = self;
+                Bytes::all_in_range($e, range)
+            }
+
+            #[inline]
+            fn first_in_range(&self, range: RangeInclusive<u8>)
+                -> Option<&u8>
+            {
+                let $x = self;
+                Bytes::first_in_range($e, range)
+            }
+
+            #[inline]
+            fn count_eq(&self, byte: u8) -> usize {
+                let $x = self;
+                Bytes::count_eq($e, byte)
+            }
+
+            #[inline]
+            fn count_ne(&self, byte: u8) -> usize {
+                let $x = self;
+                Bytes::count_ne($e, byte)
+            }
+
+            #[inline]
+            fn iter_eq(&self, byte: u8) -> Positions<'_> {
+                let $x = self;
+                Bytes::iter_eq($e, byte)
+            }
+
+            #[inline]
+            fn iter_ne(&self, byte: u8) -> Positions<'_> {
+                let $x = self;
+                Bytes::iter_ne($e, byte)
+            }
+
+            #[inline]
+            fn contains_seq(&self, needle: &[u8]) -> bool {
+                let $x = self;
+                Bytes::contains_seq($e, needle)
+            }
+
+            #[inline]
+            fn find(&self, needle: &[u8]) -> Option<usize> {
+                let $x = self;
+                Bytes::find($e, needle)
+            }
+
+            #[inline]
+            fn rfind(&self, needle: &[u8]) -> Option<usize> {
+                let $x = self;
+                Bytes::rfind($e, needle)
+            }
+        }
+    };
+}
+
+// Implements `BytesMut` for each `$t` by forwarding to `$e`, which views `$x`
+// (`self`) as another implementor.
+macro_rules! impl_bytes_mut_via {
+    ($($t:ty => |$x:ident| $e:expr,)+) => { $(
+        impl_bytes_mut_via!(@impl $t, $x, $e);
+    )+ };
+    (@impl $t:ty, $x:ident, $e:expr) => {
+        impl BytesMut for $t {
+            #[inline]
+            fn first_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
+                let $x = self;
+                BytesMut::first_eq_mut($e, byte)
+            }
+
+            #[inline]
+            fn first_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
+                let $x = self;
+                BytesMut::first_ne_mut($e, byte)
+            }
+
+            #[inline]
+            fn last_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
+                let $x = self;
+                BytesMut::last_eq_mut($e, byte)
+            }
+
+            #[inline]
+            fn last_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
+                let $x = self;
+                BytesMut::last_ne_mut($e, byte)
+            }
+
+            #[inline]
+            fn fill(&mut self, byte: u8) {
+                let $x = self;
+                BytesMut::fill($e, byte)
+            }
+
+            #[inline]
+            fn replace(&mut self, from: u8, to: u8) {
+                let $x = self;
+                BytesMut::replace($e, from, to)
+            }
+
+            #[inline]
+            fn replace_all_in(&mut self, set: &ByteSet, to: u8) {
+                let $x = self;
+                BytesMut::replace_all_in($e, set, to)
+            }
+        }
+    };
+}
 
-mod large;
+mod align;
+mod array;
+mod case;
+mod elements;
+mod iter;
 mod multi;
 mod scalar;
+mod search;
+mod set;
 mod small;
+mod table;
+
+pub mod utf8;
+
+#[cfg(all(feature = "std", any(target_arch = "x86", target_arch = "x86_64")))]
+mod x86;
 
-#[cfg(not(feature = "simd"))]
+pub use align::{AlignedChunks, AlignedChunksMut, Chunk};
+pub use case::AsciiCase;
+pub use elements::Elements;
+pub use iter::Positions;
+pub use set::ByteSet;
+pub use table::{ByteTable, translate, translate_into};
+
+#[cfg(not(feature = "portable_simd"))]
 type Batch = usize;
 
-#[cfg(all(feature = "simd", not(target_feature = "avx")))]
+#[cfg(all(feature = "portable_simd", not(target_feature = "avx")))]
 type Batch = u8x16;
 
-#[cfg(all(feature = "simd", target_feature = "avx"))]
+#[cfg(all(feature = "portable_simd", target_feature = "avx"))]
 type Batch = u8x32;
 
 /// A type that can be treated as a sequence of bytes.
+///
+/// Owned and shared buffers such as `String`, `Vec<u8>` and `Rc<[u8]>` don't
+/// implement this, since its methods would then take the place of the
+/// inherent `str` and slice methods of the same names, such as `contains` and
+/// `find`. Method calls on them still reach these through `Deref`, and generic
+/// code can be given `&v[..]` or `s.as_bytes()`:
+///
+/// ```
+/// use byte_ops::Bytes;
+///
+/// fn first_comma<B: Bytes + ?Sized>(bytes: &B) -> Option<usize> {
+///     bytes.position_eq(b',')
+/// }
+///
+/// let s = String::from("hello, world");
+/// assert!(s.contains("wor"));
+/// assert_eq!(first_comma(s.as_bytes()), Some(5));
+///
+/// let v = vec![1u8, 2, 3];
+/// assert!(v.contains(&2));
+/// assert_eq!(v.position_eq(3), Some(2));
+/// assert_eq!(first_comma(&v[..]), None);
+/// ```
 pub trait Bytes {
     /// Returns whether every byte in `self` is `byte`.
     fn is(&self, byte: u8) -> bool;
@@ -51,32 +366,178 @@ pub trait Bytes {
     /// Returns a reference to the first byte in `self` that equals `byte`.
     fn first_eq(&self, byte: u8) -> Option<&u8>;
 
-    /// Returns a mutable reference to the first byte in `self` that equals
-    /// `byte`.
-    fn first_eq_mut(&mut self, byte: u8) -> Option<&mut u8>;
-
     /// Returns a reference to the first byte in `self` that does not equal
     /// `byte`.
     fn first_ne(&self, byte: u8) -> Option<&u8>;
 
+    /// Returns a reference to the last byte in `self` that equals `byte`.
+    fn last_eq(&self, byte: u8) -> Option<&u8>;
+
+    /// Returns a reference to the last byte in `self` that does not equal
+    /// `byte`.
+    fn last_ne(&self, byte: u8) -> Option<&u8>;
+
+    /// Returns the index of the first byte in `self` that equals `byte`.
+    ///
+    /// Integers are indexed in memory order (as in `to_ne_bytes`), so this is
+    /// always the offset of the byte referenced by `first_eq`.
+    fn position_eq(&self, byte: u8) -> Option<usize>;
+
+    /// Returns the index of the first byte in `self` that does not equal
+    /// `byte`.
+    fn position_ne(&self, byte: u8) -> Option<usize>;
+
+    /// Returns the index of the last byte in `self` that equals `byte`.
+    fn rposition_eq(&self, byte: u8) -> Option<usize>;
+
+    /// Returns the index of the last byte in `self` that does not equal
+    /// `byte`.
+    fn rposition_ne(&self, byte: u8) -> Option<usize>;
+
+    /// Returns whether `self` contains either `a` or `b`.
+    #[inline]
+    fn contains_any2(&self, a: u8, b: u8) -> bool {
+        self.first_any2(a, b).is_some()
+    }
+
+    /// Returns whether `self` contains any of `a`, `b` or `c`.
+    #[inline]
+    fn contains_any3(&self, a: u8, b: u8, c: u8) -> bool {
+        self.first_any3(a, b, c).is_some()
+    }
+
+    /// Returns a reference to the first byte in `self` that equals either `a`
+    /// or `b`.
+    fn first_any2(&self, a: u8, b: u8) -> Option<&u8>;
+
+    /// Returns a reference to the first byte in `self` that equals any of
+    /// `a`, `b` or `c`.
+    fn first_any3(&self, a: u8, b: u8, c: u8) -> Option<&u8>;
+
+    /// Returns whether `self` contains a byte in `set`.
+    #[inline]
+    fn contains_in(&self, set: &ByteSet) -> bool {
+        self.first_in(set).is_some()
+    }
+
+    /// Returns whether every byte in `self` is in `set`.
+    #[inline]
+    fn all_in(&self, set: &ByteSet) -> bool {
+        self.first_in(&set.complement()).is_none()
+    }
+
+    /// Returns a reference to the first byte in `self` that is in `set`.
+    fn first_in(&self, set: &ByteSet) -> Option<&u8>;
+
+    /// Returns whether every byte in `self` is ASCII.
+    ///
+    /// Unlike `all_in_range(0..=0x7F)`, this is `true` for an empty `self`, as
+    /// with the standard library's `is_ascii`.
+    ///
+    /// Since slices and strings have an inherent `is_ascii` method, calls on
+    /// them must be written as `Bytes::is_ascii(slice)` to use this instead.
+    #[inline]
+    fn is_ascii(&self) -> bool { self.first_non_ascii().is_none() }
+
+    /// Returns a reference to the first byte in `self` that is not ASCII.
+    fn first_non_ascii(&self) -> Option<&u8>;
+
+    /// Returns whether `self` contains `byte` in either ASCII case.
+    #[inline]
+    fn contains_ignore_ascii_case(&self, byte: u8) -> bool {
+        let lower = byte.to_ascii_lowercase();
+        let upper = byte.to_ascii_uppercase();
+        if lower == upper {
+            self.contains(byte)
+        } else {
+            self.contains_any2(lower, upper)
+        }
+    }
+
+    /// Returns whether `self` contains a byte in `range`.
+    #[inline]
+    fn contains_in_range(&self, range: RangeInclusive<u8>) -> bool {
+        self.first_in_range(range).is_some()
+    }
+
+    /// Returns whether every byte in `self` is in `range`.
+    #[inline]
+    fn all_in_range(&self, range: RangeInclusive<u8>) -> bool {
+        self.all_in(&ByteSet::from_range(range))
+    }
+
+    /// Returns a reference to the first byte in `self` that is in `range`.
+    ///
+    /// Unlike with an equivalent [`ByteSet`](struct.ByteSet.html), this
+    /// compares each byte against the bounds of `range` rather than looking
+    /// it up.
+    fn first_in_range(&self, range: RangeInclusive<u8>) -> Option<&u8>;
+
+    /// Returns the number of bytes in `self` that equal `byte`.
+    fn count_eq(&self, byte: u8) -> usize;
+
+    /// Returns the number of bytes in `self` that do not equal `byte`.
+    fn count_ne(&self, byte: u8) -> usize;
+
+    /// Returns an iterator over the indices of the bytes in `self` that equal
+    /// `byte`.
+    fn iter_eq(&self, byte: u8) -> Positions<'_>;
+
+    /// Returns an iterator over the indices of the bytes in `self` that do not
+    /// equal `byte`.
+    fn iter_ne(&self, byte: u8) -> Positions<'_>;
+
+    /// Returns whether `needle` occurs as a contiguous sequence in `self`.
+    #[inline]
+    fn contains_seq(&self, needle: &[u8]) -> bool {
+        self.find(needle).is_some()
+    }
+
+    /// Returns the index of the first occurrence of `needle` in `self`.
+    ///
+    /// An empty `needle` is found at index 0.
+    fn find(&self, needle: &[u8]) -> Option<usize>;
+
+    /// Returns the index of the last occurrence of `needle` in `self`.
+    ///
+    /// An empty `needle` is found at the end of `self`.
+    fn rfind(&self, needle: &[u8]) -> Option<usize>;
+}
+
+/// A [`Bytes`](trait.Bytes.html) type whose bytes can be mutated in place.
+///
+/// This is separate from `Bytes` so that types like `NonZeroU32` can be
+/// searched without handing out references that could break their invariants.
+pub trait BytesMut: Bytes {
+    /// Returns a mutable reference to the first byte in `self` that equals
+    /// `byte`.
+    fn first_eq_mut(&mut self, byte: u8) -> Option<&mut u8>;
+
     /// Returns a mutable reference to the first byte in `self` that does not
     /// equal `byte`.
     fn first_ne_mut(&mut self, byte: u8) -> Option<&mut u8>;
 
-    /// Returns a reference to the last byte in `self` that equals `byte`.
-    fn last_eq(&self, byte: u8) -> Option<&u8>;
-
     /// Returns a mutable reference to the last byte in `self` that equals
     /// `byte`.
     fn last_eq_mut(&mut self, byte: u8) -> Option<&mut u8>;
 
-    /// Returns a reference to the last byte in `self` that does not equal
-    /// `byte`.
-    fn last_ne(&self, byte: u8) -> Option<&u8>;
-
     /// Returns a mutable reference to the last byte in `self` that does not
     /// equal `byte`.
     fn last_ne_mut(&mut self, byte: u8) -> Option<&mut u8>;
+
+    /// Sets every byte in `self` to `byte`, like
+    /// [`SizedBytes::splat`](trait.SizedBytes.html#tymethod.splat) does for a
+    /// new value.
+    ///
+    /// Since slices and arrays have an inherent `fill` method, calls on them
+    /// must be written as `BytesMut::fill(slice, byte)` to use this instead.
+    fn fill(&mut self, byte: u8);
+
+    /// Replaces every byte in `self` that equals `from` with `to`.
+    fn replace(&mut self, from: u8, to: u8);
+
+    /// Replaces every byte in `self` that is in `set` with `to`.
+    fn replace_all_in(&mut self, set: &ByteSet, to: u8);
 }
 
 impl Bytes for u8 {
@@ -91,174 +552,1012 @@ impl Bytes for u8 {
         if self.is(byte) { Some(self) } else { None }
     }
 
-    #[inline]
-    fn first_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
-        if self.is(byte) { Some(self) } else { None }
-    }
-
     #[inline]
     fn first_ne(&self, byte: u8) -> Option<&u8> {
         if !self.is(byte) { Some(self) } else { None }
     }
 
     #[inline]
-    fn first_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
-        if !self.is(byte) { Some(self) } else { None }
-    }
+    fn last_eq(&self, byte: u8) -> Option<&u8> { self.first_eq(byte) }
 
     #[inline]
-    fn last_eq(&self, byte: u8) -> Option<&u8> {
-        unimplemented!()
-    }
+    fn last_ne(&self, byte: u8) -> Option<&u8> { self.first_ne(byte) }
 
     #[inline]
-    fn last_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
-        unimplemented!()
+    fn position_eq(&self, byte: u8) -> Option<usize> {
+        if self.is(byte) { Some(0) } else { None }
     }
 
     #[inline]
-    fn last_ne(&self, byte: u8) -> Option<&u8> {
-        unimplemented!()
+    fn position_ne(&self, byte: u8) -> Option<usize> {
+        if !self.is(byte) { Some(0) } else { None }
     }
 
     #[inline]
-    fn last_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
-        unimplemented!()
+    fn rposition_eq(&self, byte: u8) -> Option<usize> {
+        self.position_eq(byte)
     }
-}
-
-// Alignment code used by the `bytecount` crate
-fn batch_align(b: &[u8]) -> (&[u8], &[Batch], &[u8]) {
-    use core::{cmp, mem, slice};
 
-    const ALIGN: usize = mem::align_of::<Batch>();
+    #[inline]
+    fn rposition_ne(&self, byte: u8) -> Option<usize> {
+        self.position_ne(byte)
+    }
 
-    let address   = b.as_ptr() as usize;
-    let align_rem = address % ALIGN;
-    let align_end = (address + b.len()) % ALIGN;
+    #[inline]
+    fn first_any2(&self, a: u8, b: u8) -> Option<&u8> {
+        if *self == a || *self == b { Some(self) } else { None }
+    }
 
-    let d2 = b.len().saturating_sub(align_end);
-    let d1 = cmp::min((ALIGN - align_rem) % ALIGN, d2);
+    #[inline]
+    fn first_any3(&self, a: u8, b: u8, c: u8) -> Option<&u8> {
+        if *self == a || *self == b || *self == c { Some(self) } else { None }
+    }
 
-    let (init, tail) = b.split_at(d2);
-    let (init, mid) = init.split_at(d1);
+    #[inline]
+    fn first_in(&self, set: &ByteSet) -> Option<&u8> {
+        if set.contains(*self) { Some(self) } else { None }
+    }
 
-    assert_eq!(mid.len() % ALIGN, 0);
-    let mid = unsafe {
-        slice::from_raw_parts(mid.as_ptr() as *const Batch, mid.len() / ALIGN)
-    };
+    #[inline]
+    fn is_ascii(&self) -> bool { *self < 0x80 }
 
-    (init, mid, tail)
-}
+    #[inline]
+    fn first_non_ascii(&self) -> Option<&u8> {
+        if *self >= 0x80 { Some(self) } else { None }
+    }
 
-macro_rules! batched {
-    ($val:expr, $byte:expr, $f:expr, $b:expr) => {
-        let (x, y, z) = batch_align($val);
+    #[inline]
+    fn all_in_range(&self, range: RangeInclusive<u8>) -> bool {
+        range.contains(self)
+    }
 
-        for batch in y {
-            if $f(batch, $byte) == $b {
-                return $b;
-            }
-        }
+    #[inline]
+    fn first_in_range(&self, range: RangeInclusive<u8>) -> Option<&u8> {
+        if range.contains(self) { Some(self) } else { None }
+    }
 
-        for &slice in &[x, z] {
-            for byte in slice {
-                if $f(byte, $byte) == $b {
-                    return $b;
-                }
-            }
-        }
+    #[inline]
+    fn count_eq(&self, byte: u8) -> usize { (*self == byte) as usize }
 
-        !$b
-    };
-}
+    #[inline]
+    fn count_ne(&self, byte: u8) -> usize { (*self != byte) as usize }
 
-impl Bytes for [u8] {
-    fn is(&self, byte: u8) -> bool {
-        if self.is_empty() {
-            return false;
-        }
-        batched! { self, byte, Bytes::is, false }
+    #[inline]
+    fn iter_eq(&self, byte: u8) -> Positions<'_> {
+        Positions::new(core::slice::from_ref(self), byte, true)
     }
 
-    fn contains(&self, byte: u8) -> bool {
-        if self.is_empty() {
-            return false;
-        }
-        batched! { self, byte, Bytes::contains, true }
+    #[inline]
+    fn iter_ne(&self, byte: u8) -> Positions<'_> {
+        Positions::new(core::slice::from_ref(self), byte, false)
     }
 
     #[inline]
-    fn first_eq(&self, byte: u8) -> Option<&u8> {
-        unimplemented!()
+    fn find(&self, needle: &[u8]) -> Option<usize> {
+        search::find(core::slice::from_ref(self), needle)
     }
 
     #[inline]
-    fn first_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
-        unimplemented!()
+    fn rfind(&self, needle: &[u8]) -> Option<usize> {
+        search::rfind(core::slice::from_ref(self), needle)
     }
+}
 
+impl BytesMut for u8 {
     #[inline]
-    fn first_ne(&self, byte: u8) -> Option<&u8> {
-        unimplemented!()
+    fn first_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
+        if self.is(byte) { Some(self) } else { None }
     }
 
     #[inline]
     fn first_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
-        unimplemented!()
+        if !self.is(byte) { Some(self) } else { None }
     }
 
     #[inline]
-    fn last_eq(&self, byte: u8) -> Option<&u8> {
-        unimplemented!()
+    fn last_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
+        self.first_eq_mut(byte)
     }
 
     #[inline]
-    fn last_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
-        unimplemented!()
+    fn last_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
+        self.first_ne_mut(byte)
     }
 
     #[inline]
-    fn last_ne(&self, byte: u8) -> Option<&u8> {
-        unimplemented!()
+    fn fill(&mut self, byte: u8) { *self = byte }
+
+    #[inline]
+    fn replace(&mut self, from: u8, to: u8) {
+        if *self == from {
+            *self = to;
+        }
     }
 
     #[inline]
-    fn last_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
-        unimplemented!()
+    fn replace_all_in(&mut self, set: &ByteSet, to: u8) {
+        if set.contains(*self) {
+            *self = to;
+        }
     }
 }
 
-/// A type that can be treated as a statically-sized sequence of bytes.
-pub trait SizedBytes: Sized + Bytes {
-    /// Creates a new instance with `byte` duplicated over all bytes.
-    fn splat(byte: u8) -> Self where Self: Sized;
+// Splits `b` around its aligned batches; see `AlignedChunks`.
+#[inline]
+fn batch_align(b: &[u8]) -> (&[u8], &[Batch], &[u8]) {
+    let chunks = AlignedChunks::new(b);
+    (chunks.prefix, chunks.batches, chunks.suffix)
 }
 
-impl SizedBytes for u8 {
-    #[inline]
-    fn splat(byte: u8) -> u8 { byte }
+// Per-batch matching used to locate bytes within a slice.
+//
+// Bit `i * STRIDE` of a mask corresponds to the byte at offset `i` in memory,
+// which keeps bit scans independent of endianness.
+trait Matches: Copy {
+    // The number of mask bits per byte.
+    const STRIDE: u32;
+
+    // Returns a mask with the bits of each byte equal to `byte` set.
+    fn eq_bits(self, byte: u8) -> u64;
+
+    // Returns a mask with the bits of each byte not equal to `byte` set.
+    fn ne_bits(self, byte: u8) -> u64;
+
+    // Returns a mask with the bits of each byte in `set` set.
+    fn in_bits(self, set: &ByteSet) -> u64;
+
+    // Returns a mask with the bits of each byte in `range` set.
+    fn range_bits(self, range: ByteRange) -> u64;
+
+    // Returns a mask with the bits of each non-ASCII byte set.
+    fn high_bits(self) -> u64;
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use rand::{Rng, thread_rng};
+// Per-batch rewriting of bytes within a slice, the counterpart to `Matches`.
+trait Rewrite: Matches {
+    // Returns `self` with `bits` flipped in each byte in `range`.
+    fn flip_in_range(self, range: ByteRange, bits: u8) -> Self;
 
-    assert_obj_safe!(__; Bytes);
+    // Returns `self` with each byte equal to `from` replaced with `to`.
+    fn replace(self, from: u8, to: u8) -> Self;
 
-    macro_rules! all_bytes {
-        ($f:expr) => {
-            let mut n = 0u8;
-            loop {
-                $f(n);
-                if n == u8::max_value() {
+    // Returns `self` with each byte in `set` replaced with `to`.
+    fn replace_in(self, set: &ByteSet, to: u8) -> Self;
+}
+
+// Rewrites each aligned batch of `b` with `f` and every other byte with `g`.
+#[inline]
+fn rewrite<F, G>(b: &mut [u8], f: F, g: G)
+    where F: Fn(Batch) -> Batch, G: Fn(u8) -> u8
+{
+    let chunks = AlignedChunksMut::<Batch>::new(b);
+
+    for batch in chunks.batches {
+        *batch = f(*batch);
+    }
+    for byte in chunks.prefix.iter_mut().chain(chunks.suffix) {
+        *byte = g(*byte);
+    }
+}
+
+// A range of bytes that may wrap around from `0xFF` to `0x00`, so that the
+// bytes outside of one form another.
+#[derive(Clone, Copy)]
+struct ByteRange {
+    // The first byte
+    lo: u8,
+    // The number of bytes after `lo`
+    span: u8,
+}
+
+impl ByteRange {
+    // Returns the bytes in `range`, unless there are none.
+    #[inline]
+    fn new(range: RangeInclusive<u8>) -> Option<ByteRange> {
+        if range.is_empty() {
+            return None;
+        }
+        let (lo, hi) = range.into_inner();
+        Some(ByteRange { lo, span: hi - lo })
+    }
+
+    // Returns the bytes not in `self`, unless there are none.
+    #[inline]
+    fn complement(self) -> Option<ByteRange> {
+        match self.span {
+            0xFF => None,
+            span => Some(ByteRange {
+                lo: self.lo.wrapping_add(span).wrapping_add(1),
+                span: 0xFE - span,
+            }),
+        }
+    }
+
+    #[inline]
+    fn contains(self, byte: u8) -> bool {
+        byte.wrapping_sub(self.lo) <= self.span
+    }
+}
+
+// Returns whether every byte of a nonempty value is in `range`, where `any`
+// returns whether the value contains a byte in a given range.
+#[inline]
+fn all_in_range<F>(range: RangeInclusive<u8>, any: F) -> bool
+    where F: FnOnce(ByteRange) -> bool
+{
+    match ByteRange::new(range) {
+        None => false,
+        Some(range) => match range.complement() {
+            None => true,
+            Some(c) => !any(c),
+        },
+    }
+}
+
+// Returns the offset of the first byte marked in a mask from `T`.
+#[inline]
+fn first_pos<T: Matches>(mask: u64) -> Option<usize> {
+    match mask {
+        0 => None,
+        _ => Some((mask.trailing_zeros() / T::STRIDE) as usize),
+    }
+}
+
+// Returns the offset of the last byte marked in a mask from `T`.
+#[inline]
+fn last_pos<T: Matches>(mask: u64) -> Option<usize> {
+    match mask {
+        0 => None,
+        _ => Some(((63 - mask.leading_zeros()) / T::STRIDE) as usize),
+    }
+}
+
+// Returns the offset of the first byte within `batches` marked by `m`.
+#[inline]
+fn first_batch<T, M>(batches: &[T], m: M) -> Option<usize>
+    where T: Matches, M: Fn(T) -> u64
+{
+    for (i, &batch) in batches.iter().enumerate() {
+        if let Some(pos) = first_pos::<T>(m(batch)) {
+            return Some(i * core::mem::size_of::<T>() + pos);
+        }
+    }
+    None
+}
+
+// Returns the offset of the last byte within `batches` marked by `m`.
+#[inline]
+fn last_batch<T, M>(batches: &[T], m: M) -> Option<usize>
+    where T: Matches, M: Fn(T) -> u64
+{
+    for (i, &batch) in batches.iter().enumerate().rev() {
+        if let Some(pos) = last_pos::<T>(m(batch)) {
+            return Some(i * core::mem::size_of::<T>() + pos);
+        }
+    }
+    None
+}
+
+// Gathers the bits of a mask with a stride of 8 into its lowest byte.
+//
+// Multiplying places each byte's bit at a distinct offset, so no carries
+// disturb the gathered bits in the top byte.
+#[inline]
+fn compact(mask: u64) -> u64 {
+    (mask >> 7).wrapping_mul(0x0102_0408_1020_4080) >> 56
+}
+
+// Returns the number of bytes within `batches` marked by `m`.
+#[inline]
+fn count_batches<T, M>(batches: &[T], m: M) -> usize
+    where T: Matches, M: Fn(T) -> u64
+{
+    if T::STRIDE == 1 {
+        return batches.iter().map(|&b| m(b).count_ones() as usize).sum();
+    }
+
+    // Sum the marks of each byte separately and only add those sums together
+    // before any of them can overflow
+    let mut count = 0;
+    for chunk in batches.chunks(255) {
+        let mut sums = 0u64;
+        for &batch in chunk {
+            sums += m(batch) >> (T::STRIDE - 1);
+        }
+        count += sum_bytes(sums);
+    }
+    count
+}
+
+// Returns the sum of the bytes in `x`.
+#[inline]
+fn sum_bytes(x: u64) -> usize {
+    const PAIRS: u64 = 0x00FF_00FF_00FF_00FF;
+
+    let x = (x & PAIRS) + (x >> 8 & PAIRS);
+    (x.wrapping_mul(0x0001_0001_0001_0001) >> 48) as usize
+}
+
+// Returns the index of the first byte in `b` that satisfies `f`, testing the
+// aligned middle a whole batch at a time via the mask returned by `m`.
+#[inline]
+fn first_index<F, M>(b: &[u8], f: F, m: M) -> Option<usize>
+    where F: Fn(u8) -> bool, M: Fn(Batch) -> u64
+{
+    let (x, y, z) = batch_align(b);
+
+    if let Some(i) = x.iter().position(|&byte| f(byte)) {
+        return Some(i);
+    }
+    if let Some(i) = first_batch(y, m) {
+        return Some(x.len() + i);
+    }
+    z.iter().position(|&byte| f(byte)).map(|i| b.len() - z.len() + i)
+}
+
+// Returns the index of the last byte in `b` that satisfies `f`, walking the
+// aligned middle back to front a whole batch at a time.
+#[inline]
+fn last_index<F, M>(b: &[u8], f: F, m: M) -> Option<usize>
+    where F: Fn(u8) -> bool, M: Fn(Batch) -> u64
+{
+    let (x, y, z) = batch_align(b);
+
+    if let Some(i) = z.iter().rposition(|&byte| f(byte)) {
+        return Some(b.len() - z.len() + i);
+    }
+    if let Some(i) = last_batch(y, m) {
+        return Some(x.len() + i);
+    }
+    x.iter().rposition(|&byte| f(byte))
+}
+
+// Returns the number of bytes in `b` that satisfy `f`, counting the aligned
+// middle a whole batch at a time.
+#[inline]
+fn count_index<F, M>(b: &[u8], f: F, m: M) -> usize
+    where F: Fn(u8) -> bool, M: Fn(Batch) -> u64
+{
+    let (x, y, z) = batch_align(b);
+    let ends = x.iter().chain(z).filter(|&&byte| f(byte)).count();
+    ends + count_batches(y, m)
+}
+
+// Returns the index of the first byte in `b` that equals `x` or `y`.
+#[inline]
+fn position_any2(b: &[u8], x: u8, y: u8) -> Option<usize> {
+    with_x86!(|k| (k.position_any2)(b, x, y));
+    first_index(b, |c| c == x || c == y, |c| c.eq_bits(x) | c.eq_bits(y))
+}
+
+// Returns the index of the first byte in `b` that equals `x`, `y` or `z`.
+#[inline]
+fn position_any3(b: &[u8], x: u8, y: u8, z: u8) -> Option<usize> {
+    with_x86!(|k| (k.position_any3)(b, x, y, z));
+    first_index(b, |c| c == x || c == y || c == z, |c| {
+        c.eq_bits(x) | c.eq_bits(y) | c.eq_bits(z)
+    })
+}
+
+// Returns the index of the first byte in `b` that is in `r`.
+#[inline]
+fn position_in_range(b: &[u8], r: ByteRange) -> Option<usize> {
+    with_x86!(|k| (k.position_range)(b, r.lo, r.span));
+    first_index(b, |x| r.contains(x), |x| x.range_bits(r))
+}
+
+// Returns the index of the first byte in `b` that isn't ASCII.
+#[inline]
+fn position_non_ascii(b: &[u8]) -> Option<usize> {
+    with_x86!(|k| (k.position_range)(b, 0x80, 0x7F));
+    first_index(b, |x| x >= 0x80, |x| x.high_bits())
+}
+
+// Returns the bytes of `val`.
+#[inline]
+unsafe fn as_bytes<T>(val: &T) -> &[u8] {
+    let ptr = val as *const T as *const u8;
+    core::slice::from_raw_parts(ptr, core::mem::size_of::<T>())
+}
+
+// Returns a reference to the byte at offset `i` within `val`.
+#[inline]
+unsafe fn byte_at<T>(val: &T, i: usize) -> &u8 {
+    &*(val as *const T as *const u8).add(i)
+}
+
+// Returns a mutable reference to the byte at offset `i` within `val`.
+#[inline]
+unsafe fn byte_at_mut<T>(val: &mut T, i: usize) -> &mut u8 {
+    &mut *(val as *mut T as *mut u8).add(i)
+}
+
+// Returns `val` viewed as a `U`, which must have the same layout as `T`.
+#[inline]
+unsafe fn cast_ref<T, U>(val: &T) -> &U {
+    &*(val as *const T as *const U)
+}
+
+// Returns `val` viewed as a mutable `U`, which must have the same layout as
+// `T` and accept any bytes it is given.
+#[inline]
+unsafe fn cast_mut<T, U>(val: &mut T) -> &mut U {
+    &mut *(val as *mut T as *mut U)
+}
+
+impl Bytes for [u8] {
+    fn is(&self, byte: u8) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        with_x86!(|k| (k.position)(self, byte, false).is_none());
+        let chunks = AlignedChunks::<Batch>::new(self);
+        chunks.all_batch(|x| x.is(byte), |b| b == byte)
+    }
+
+    fn contains(&self, byte: u8) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        with_x86!(|k| (k.position)(self, byte, true).is_some());
+        let chunks = AlignedChunks::<Batch>::new(self);
+        chunks.any_batch(|x| x.contains(byte), |b| b == byte)
+    }
+
+    #[inline]
+    fn first_eq(&self, byte: u8) -> Option<&u8> {
+        let i = self.position_eq(byte)?;
+        Some(unsafe { self.get_unchecked(i) })
+    }
+
+    #[inline]
+    fn first_ne(&self, byte: u8) -> Option<&u8> {
+        let i = self.position_ne(byte)?;
+        Some(unsafe { self.get_unchecked(i) })
+    }
+
+    #[inline]
+    fn last_eq(&self, byte: u8) -> Option<&u8> {
+        let i = self.rposition_eq(byte)?;
+        Some(unsafe { self.get_unchecked(i) })
+    }
+
+    #[inline]
+    fn last_ne(&self, byte: u8) -> Option<&u8> {
+        let i = self.rposition_ne(byte)?;
+        Some(unsafe { self.get_unchecked(i) })
+    }
+
+    #[inline]
+    fn position_eq(&self, byte: u8) -> Option<usize> {
+        with_x86!(|k| (k.position)(self, byte, true));
+        first_index(self, |b| b == byte, |b| b.eq_bits(byte))
+    }
+
+    #[inline]
+    fn position_ne(&self, byte: u8) -> Option<usize> {
+        with_x86!(|k| (k.position)(self, byte, false));
+        first_index(self, |b| b != byte, |b| b.ne_bits(byte))
+    }
+
+    #[inline]
+    fn rposition_eq(&self, byte: u8) -> Option<usize> {
+        with_x86!(|k| (k.rposition)(self, byte, true));
+        last_index(self, |b| b == byte, |b| b.eq_bits(byte))
+    }
+
+    #[inline]
+    fn rposition_ne(&self, byte: u8) -> Option<usize> {
+        with_x86!(|k| (k.rposition)(self, byte, false));
+        last_index(self, |b| b != byte, |b| b.ne_bits(byte))
+    }
+
+    #[inline]
+    fn first_any2(&self, a: u8, b: u8) -> Option<&u8> {
+        let i = position_any2(self, a, b)?;
+        Some(unsafe { self.get_unchecked(i) })
+    }
+
+    #[inline]
+    fn first_any3(&self, a: u8, b: u8, c: u8) -> Option<&u8> {
+        let i = position_any3(self, a, b, c)?;
+        Some(unsafe { self.get_unchecked(i) })
+    }
+
+    #[inline]
+    fn all_in(&self, set: &ByteSet) -> bool {
+        !self.is_empty() && self.first_in(&set.complement()).is_none()
+    }
+
+    #[inline]
+    fn first_in(&self, set: &ByteSet) -> Option<&u8> {
+        let i = first_index(self, |x| set.contains(x), |x| x.in_bits(set))?;
+        Some(unsafe { self.get_unchecked(i) })
+    }
+
+    #[inline]
+    fn is_ascii(&self) -> bool {
+        with_x86!(|k| (k.position_range)(self, 0x80, 0x7F).is_none());
+        let chunks = AlignedChunks::<Batch>::new(self);
+        chunks.all_batch(|x| x.high_bits() == 0, |b| b < 0x80)
+    }
+
+    #[inline]
+    fn first_non_ascii(&self) -> Option<&u8> {
+        let i = position_non_ascii(self)?;
+        Some(unsafe { self.get_unchecked(i) })
+    }
+
+    #[inline]
+    fn all_in_range(&self, range: RangeInclusive<u8>) -> bool {
+        !self.is_empty() && all_in_range(range, |r| {
+            position_in_range(self, r).is_some()
+        })
+    }
+
+    #[inline]
+    fn first_in_range(&self, range: RangeInclusive<u8>) -> Option<&u8> {
+        let i = position_in_range(self, ByteRange::new(range)?)?;
+        Some(unsafe { self.get_unchecked(i) })
+    }
+
+    #[inline]
+    fn count_eq(&self, byte: u8) -> usize {
+        with_x86!(|k| (k.count)(self, byte));
+        count_index(self, |b| b == byte, |b| b.eq_bits(byte))
+    }
+
+    #[inline]
+    fn count_ne(&self, byte: u8) -> usize {
+        with_x86!(|k| self.len() - (k.count)(self, byte));
+        count_index(self, |b| b != byte, |b| b.ne_bits(byte))
+    }
+
+    #[inline]
+    fn iter_eq(&self, byte: u8) -> Positions<'_> {
+        Positions::new(self, byte, true)
+    }
+
+    #[inline]
+    fn iter_ne(&self, byte: u8) -> Positions<'_> {
+        Positions::new(self, byte, false)
+    }
+
+    #[inline]
+    fn find(&self, needle: &[u8]) -> Option<usize> {
+        search::find(self, needle)
+    }
+
+    #[inline]
+    fn rfind(&self, needle: &[u8]) -> Option<usize> {
+        search::rfind(self, needle)
+    }
+}
+
+impl BytesMut for [u8] {
+    #[inline]
+    fn first_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
+        let i = self.position_eq(byte)?;
+        Some(unsafe { self.get_unchecked_mut(i) })
+    }
+
+    #[inline]
+    fn first_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
+        let i = self.position_ne(byte)?;
+        Some(unsafe { self.get_unchecked_mut(i) })
+    }
+
+    #[inline]
+    fn last_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
+        let i = self.rposition_eq(byte)?;
+        Some(unsafe { self.get_unchecked_mut(i) })
+    }
+
+    #[inline]
+    fn last_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
+        let i = self.rposition_ne(byte)?;
+        Some(unsafe { self.get_unchecked_mut(i) })
+    }
+
+    #[inline]
+    fn fill(&mut self, byte: u8) {
+        let splat = Batch::splat(byte);
+        rewrite(self, |_| splat, |_| byte);
+    }
+
+    #[inline]
+    fn replace(&mut self, from: u8, to: u8) {
+        rewrite(self, |x| x.replace(from, to), |b| {
+            if b == from { to } else { b }
+        });
+    }
+
+    #[inline]
+    fn replace_all_in(&mut self, set: &ByteSet, to: u8) {
+        rewrite(self, |x| x.replace_in(set, to), |b| {
+            if set.contains(b) { to } else { b }
+        });
+    }
+}
+
+// Strings are only searched, since writing to their bytes could leave them as
+// invalid UTF-8
+impl_bytes_via! {
+    str => |x| x.as_bytes(),
+}
+
+/// A type that can be treated as a statically-sized sequence of bytes.
+pub trait SizedBytes: Sized + BytesMut {
+    /// Creates a new instance with `byte` duplicated over all bytes.
+    fn splat(byte: u8) -> Self where Self: Sized;
+}
+
+impl SizedBytes for u8 {
+    #[inline]
+    fn splat(byte: u8) -> u8 { byte }
+}
+
+/// A statically-sized sequence of bytes that can be compared into a bitmask,
+/// like `movemask` on a vector of byte comparisons.
+///
+/// Bit `i` of a mask corresponds to the byte at index `i`, as returned by
+/// [`position_eq`](trait.Bytes.html#tymethod.position_eq).
+pub trait MaskBytes: SizedBytes {
+    /// An unsigned integer with at least one bit per byte in `Self`.
+    type Mask;
+
+    /// Returns a mask with the bit of each byte that equals `byte` set.
+    fn eq_mask(&self, byte: u8) -> Self::Mask;
+
+    /// Returns a mask with the bit of each byte that does not equal `byte`
+    /// set.
+    fn ne_mask(&self, byte: u8) -> Self::Mask;
+}
+
+impl MaskBytes for u8 {
+    type Mask = u8;
+
+    #[inline]
+    fn eq_mask(&self, byte: u8) -> u8 { (*self == byte) as u8 }
+
+    #[inline]
+    fn ne_mask(&self, byte: u8) -> u8 { (*self != byte) as u8 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, thread_rng};
+
+    #[cfg(not(feature = "std"))]
+    use std::vec::Vec;
+
+    assert_obj_safe!(__; dyn Bytes);
+
+    // Returns the offset of `byte` within `val`.
+    fn offset<T: ?Sized>(val: &T, byte: Option<&u8>) -> Option<usize> {
+        let start = val as *const T as *const u8 as usize;
+        byte.map(|b| b as *const u8 as usize - start)
+    }
+
+    macro_rules! all_bytes {
+        ($f:expr) => {
+            let mut n = 0u8;
+            loop {
+                $f(n);
+                if n == u8::MAX {
                     break;
                 } else {
                     n += 1;
                 }
             }
         };
+        // Calls `$f::<T>(byte)` for every byte and every type `T`
+        ($f:ident => $($t:ty)+) => { $(
+            all_bytes!(|b| $f::<$t>(b));
+        )+ };
+    }
+
+    // Checks every `Bytes` method of `val` for `byte` against the same
+    // operation done naively on `bytes`, the contents of `val`.
+    fn check<B: Bytes + ?Sized>(val: &B, bytes: &[u8], byte: u8) {
+        check_at(val, val as *const B as *const u8, bytes, byte);
+    }
+
+    // Like `check`, but for a `val` whose contents are stored at `start`
+    // rather than inline.
+    fn check_at<B: Bytes + ?Sized>(val: &B, start: *const u8, bytes: &[u8],
+                                   byte: u8) {
+        let offset = |x: Option<&u8>| x.map(|x| x as *const u8 as usize -
+                                               start as usize);
+        let b = byte;
+        let c = byte.wrapping_add(1);
+        let d = byte.wrapping_add(2);
+        let set = ByteSet::from_bytes(&[b, d]);
+
+        let eq = |x: &u8| *x == b;
+        let ne = |x: &u8| *x != b;
+        let find = |f: &dyn Fn(&u8) -> bool| bytes.iter().position(f);
+        let rfind = |f: &dyn Fn(&u8) -> bool| bytes.iter().rposition(f);
+        let count = |f: &dyn Fn(&u8) -> bool| bytes.iter().filter(|x| f(x))
+                                                          .count();
+        let indices = |f: &dyn Fn(&u8) -> bool| {
+            (0..bytes.len()).filter(|&i| f(&bytes[i])).collect::<Vec<_>>()
+        };
+
+        let nonempty = !bytes.is_empty();
+        assert_eq!(val.is(b), nonempty && bytes.iter().all(eq));
+        assert_eq!(val.is_zero(), nonempty && bytes.iter().all(|&x| x == 0));
+        assert_eq!(val.contains(b), bytes.contains(&b));
+        assert_eq!(val.contains_zero(), bytes.contains(&0));
+
+        assert_eq!(offset(val.first_eq(b)), find(&eq));
+        assert_eq!(offset(val.first_ne(b)), find(&ne));
+        assert_eq!(offset(val.last_eq(b)), rfind(&eq));
+        assert_eq!(offset(val.last_ne(b)), rfind(&ne));
+
+        assert_eq!(val.position_eq(b), find(&eq));
+        assert_eq!(val.position_ne(b), find(&ne));
+        assert_eq!(val.rposition_eq(b), rfind(&eq));
+        assert_eq!(val.rposition_ne(b), rfind(&ne));
+
+        let any2 = find(&|&x| x == b || x == c);
+        let any3 = find(&|&x| x == b || x == c || x == d);
+        assert_eq!(val.contains_any2(b, c), any2.is_some());
+        assert_eq!(val.contains_any3(b, c, d), any3.is_some());
+        assert_eq!(offset(val.first_any2(b, c)), any2);
+        assert_eq!(offset(val.first_any3(b, c, d)), any3);
+
+        let first_in = find(&|&x| set.contains(x));
+        let all_in = nonempty && bytes.iter().all(|&x| set.contains(x));
+        assert_eq!(val.contains_in(&set), first_in.is_some());
+        assert_eq!(val.all_in(&set), all_in);
+        assert_eq!(offset(val.first_in(&set)), first_in);
+
+        let non_ascii = find(&|&x| x >= 0x80);
+        assert_eq!(val.is_ascii(), non_ascii.is_none());
+        assert_eq!(offset(val.first_non_ascii()), non_ascii);
+
+        // A range, an empty range unless `c` wraps to 0, and a prefix range
+        for range in [b..=d, c..=b, 0..=b] {
+            let first = find(&|x| range.contains(x));
+            let all = nonempty && bytes.iter().all(|x| range.contains(x));
+            assert_eq!(val.contains_in_range(range.clone()), first.is_some());
+            assert_eq!(val.all_in_range(range.clone()), all);
+            assert_eq!(offset(val.first_in_range(range)), first);
+        }
+
+        assert_eq!(val.count_eq(b), count(&eq));
+        assert_eq!(val.count_ne(b), count(&ne));
+        assert_eq!(val.iter_eq(b).collect::<Vec<_>>(), indices(&eq));
+        assert_eq!(val.iter_ne(b).rev().collect::<Vec<_>>(),
+                   indices(&ne).into_iter().rev().collect::<Vec<_>>());
+
+        let mid = bytes.len() / 3;
+        let needle = &bytes[mid..bytes.len().min(mid + 3)];
+        for needle in &[needle, &[b, c][..]] {
+            let windows = || bytes.windows(needle.len().max(1));
+            let (first, last) = match needle.len() {
+                0 => (Some(0), Some(bytes.len())),
+                _ => (windows().position(|w| w == *needle),
+                      windows().rposition(|w| w == *needle)),
+            };
+            assert_eq!(val.contains_seq(needle), first.is_some());
+            assert_eq!(val.find(needle), first);
+            assert_eq!(val.rfind(needle), last);
+        }
+    }
+
+    // Checks the `BytesMut` methods of `val` as well as those in `check`.
+    fn check_mut<B: BytesMut + ?Sized>(val: &mut B, bytes: &[u8], byte: u8) {
+        let start = val as *const B as *const u8;
+        check_mut_at(val, start, bytes, byte);
+    }
+
+    // Like `check_mut`, but for a `val` whose contents are stored at `start`
+    // rather than inline.
+    fn check_mut_at<B: BytesMut + ?Sized>(val: &mut B, start: *const u8,
+                                          bytes: &[u8], byte: u8) {
+        check_at(val, start, bytes, byte);
+
+        let first = |f: &dyn Fn(&u8) -> bool| bytes.iter().position(f);
+        let last = |f: &dyn Fn(&u8) -> bool| bytes.iter().rposition(f);
+        let eq = |x: &u8| *x == byte;
+        let ne = |x: &u8| *x != byte;
+
+        let at = |x: Option<&mut u8>| x.map(|x| x as *mut u8 as usize -
+                                               start as usize);
+
+        assert_eq!(at(val.first_eq_mut(byte)), first(&eq));
+        assert_eq!(at(val.first_ne_mut(byte)), first(&ne));
+        assert_eq!(at(val.last_eq_mut(byte)), last(&eq));
+        assert_eq!(at(val.last_ne_mut(byte)), last(&ne));
+
+        // These change the contents of `val`, so they come last
+        let contents = || unsafe {
+            core::slice::from_raw_parts(start, bytes.len()).to_vec()
+        };
+        let other = byte.wrapping_add(1);
+        let set = ByteSet::from_bytes(&[byte, other]);
+
+        val.replace(byte, other);
+        let replaced = bytes.iter().map(|&x| if x == byte { other } else { x });
+        assert_eq!(contents(), replaced.collect::<Vec<_>>());
+
+        val.replace_all_in(&set, byte);
+        let replaced = bytes.iter().map(|&x| {
+            if set.contains(x) { byte } else { x }
+        });
+        assert_eq!(contents(), replaced.collect::<Vec<_>>());
+
+        val.fill(other);
+        assert_eq!(contents(), vec![other; bytes.len()]);
+    }
+
+    // Checks every `Bytes` and `SizedBytes` method of `T` for `byte`, with
+    // contents that have few distinct bytes to make for many matches.
+    fn check_sized<T: SizedBytes>(byte: u8) {
+        use core::{mem, ptr};
+
+        let mut bytes = vec![0u8; mem::size_of::<T>()];
+        for (i, x) in bytes.iter_mut().enumerate() {
+            *x = (i * 7 % 5) as u8;
+        }
+        let mut val: T = unsafe { ptr::read_unaligned(bytes.as_ptr() as _) };
+        check_mut(&mut val, &bytes, byte);
+
+        let mut splat = T::splat(byte);
+        check_mut(&mut splat, &vec![byte; mem::size_of::<T>()], byte);
+    }
+
+    // Calls every method on every type; see `check`
+    #[test]
+    fn matrix() {
+        all_bytes! { check_sized =>
+            u8 i8 u16 i16 u32 i32 u64 i64 u128 i128 usize isize
+            [u8; 2] [u8; 4] [u8; 8] [u8; 16] [u8; 32] [u8; 64]
+        }
+
+        // Lengths that split into every combination of batches, words and
+        // smaller parts
+        all_bytes! { check_sized =>
+            [u8; 0] [u8; 1] [u8; 3] [u8; 5] [u8; 6] [u8; 7] [u8; 12] [u8; 15]
+            [u8; 20] [u8; 31] [u8; 33] [u8; 47] [u8; 63]
+        }
+
+        #[cfg(feature = "portable_simd")]
+        all_bytes! { check_sized =>
+            u8x2 u8x4 u8x8 u8x16 u8x32 u8x64
+        }
+
+        // Large arrays are slow to check naively, so only check the bytes
+        // they contain and one they don't
+        for &byte in &[0, 1, 4, 5] {
+            check_sized::<[u8; 128]>(byte);
+            check_sized::<[u8; 192]>(byte);
+            check_sized::<[u8; 256]>(byte);
+            check_sized::<[u8; 320]>(byte);
+            check_sized::<[u8; 384]>(byte);
+            check_sized::<[u8; 448]>(byte);
+            check_sized::<[u8; 512]>(byte);
+            check_sized::<[u8; 768]>(byte);
+            check_sized::<[u8; 1024]>(byte);
+            check_sized::<[u8; 1280]>(byte);
+            check_sized::<[u8; 1536]>(byte);
+            check_sized::<[u8; 1792]>(byte);
+            check_sized::<[u8; 2048]>(byte);
+            check_sized::<[u8; 3072]>(byte);
+            check_sized::<[u8; 4096]>(byte);
+            check_sized::<[u8; 5120]>(byte);
+            check_sized::<[u8; 6144]>(byte);
+            check_sized::<[u8; 7168]>(byte);
+            check_sized::<[u8; 65]>(byte);
+            check_sized::<[u8; 100]>(byte);
+            check_sized::<[u8; 1000]>(byte);
+
+            let bytes: Vec<u8> = (0..300).map(|i| (i * 7 % 5) as u8).collect();
+            for start in 0..8 {
+                for &len in &[0, 1, 5, 9, 31, 100, 292] {
+                    let mut vec = bytes[start..start + len].to_vec();
+                    check_mut(&mut vec[..], &bytes[start..start + len], byte);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn non_zero() {
+        use core::num::*;
+
+        macro_rules! test {
+            ($($t:ident $i:ident,)+) => { $({
+                let values = [1, $i::MAX, $i::MIN, 0x0201 as u16 as $i];
+                for &x in values.iter().filter(|&&x| x != 0) {
+                    let val = $t::new(x).unwrap();
+                    all_bytes!(|b| check(&val, &x.to_ne_bytes(), b));
+                }
+            })+ };
+        }
+
+        test! {
+            NonZeroU8 u8, NonZeroI8 i8, NonZeroU16 u16, NonZeroI16 i16,
+            NonZeroU32 u32, NonZeroI32 i32, NonZeroU64 u64, NonZeroI64 i64,
+            NonZeroU128 u128, NonZeroI128 i128,
+            NonZeroUsize usize, NonZeroIsize isize,
+        }
+    }
+
+    #[test]
+    fn str() {
+        let text = "ab,c\r\nd\u{e9}f,,\n";
+        all_bytes!(|b| check(text, text.as_bytes(), b));
+    }
+
+    // Checks every x86 backend the CPU supports, as well as the portable
+    // batch code they take the place of on slices
+    #[cfg(all(feature = "std",
+              any(target_arch = "x86", target_arch = "x86_64")))]
+    #[test]
+    fn x86() {
+        use x86::Backend;
+
+        let mut bytes: Vec<u8> = (0..300).map(|i| (i * 7 % 5) as u8).collect();
+        bytes[60] = 0x90;
+        let backends = Backend::ALL.iter().filter(|b| b.is_available());
+
+        for kernels in backends.map(|b| b.kernels()) {
+            for &byte in &[0, 1, 4, 5] {
+                for start in 0..8 {
+                    for len in (0..80).chain(vec![100, 255, 292]) {
+                        let s = &bytes[start..start + len];
+                        let eq = |&x: &u8| x == byte;
+                        let ne = |&x: &u8| x != byte;
+
+                        unsafe {
+                            assert_eq!((kernels.position)(s, byte, true),
+                                       s.iter().position(eq));
+                            assert_eq!((kernels.position)(s, byte, false),
+                                       s.iter().position(ne));
+                            assert_eq!((kernels.rposition)(s, byte, true),
+                                       s.iter().rposition(eq));
+                            assert_eq!((kernels.rposition)(s, byte, false),
+                                       s.iter().rposition(ne));
+                            assert_eq!((kernels.count)(s, byte),
+                                       s.iter().filter(|x| eq(x)).count());
+
+                            let (c, d) = (byte + 2, 3);
+                            assert_eq!((kernels.position_any2)(s, byte, c),
+                                       s.iter().position(|&x| {
+                                           x == byte || x == c
+                                       }));
+                            assert_eq!((kernels.position_any3)(s, byte, c, d),
+                                       s.iter().position(|&x| {
+                                           x == byte || x == c || x == d
+                                       }));
+
+                            // Including one that wraps and the non-ASCII bytes
+                            let ranges = [(byte, 1), (byte + 3, 0xFD),
+                                          (0x80, 0x7F)];
+                            for &(lo, span) in &ranges {
+                                let r = ByteRange { lo, span };
+                                let f = kernels.position_range;
+                                assert_eq!(f(s, lo, span),
+                                           s.iter().position(|&x| {
+                                               r.contains(x)
+                                           }));
+                            }
+                        }
+
+                        let e = |x: u8| x == byte;
+                        let m = |b: Batch| b.eq_bits(byte);
+                        assert_eq!(first_index(s, e, m), s.iter().position(eq));
+                        assert_eq!(last_index(s, e, m), s.iter().rposition(eq));
+                        assert_eq!(count_index(s, e, m),
+                                   s.iter().filter(|x| eq(x)).count());
+                    }
+                }
+            }
+        }
     }
 
     #[test]
@@ -275,22 +1574,100 @@ mod tests {
             })+ };
         }
 
-        // Test up to 32 due to current trait system,
-        // and 64 uses the same implementations as 32
+        // Test up to 32 due to `rand` only generating arrays that long, with
+        // longer arrays covered by `large_array`
         test! { 2 4 8 16 32 }
     }
 
-    #[cfg(feature = "simd")]
     #[test]
-    fn simd() {
-        use core::mem;
+    fn array_positions() {
+        let mut rng = thread_rng();
+
+        macro_rules! test {
+            ($($n:expr)+) => { $({
+                let mut arr: [u8; $n] = rng.gen();
+                all_bytes!(|b| {
+                    assert_eq!(arr.position_eq(b),
+                               arr.iter().position(|&x| x == b));
+                    assert_eq!(arr.position_ne(b),
+                               arr.iter().position(|&x| x != b));
+
+                    let eq = arr.iter().rposition(|&x| x == b);
+                    let ne = arr.iter().rposition(|&x| x != b);
+                    assert_eq!(arr.rposition_eq(b), eq);
+                    assert_eq!(arr.rposition_ne(b), ne);
+                    assert_eq!(offset(&arr, arr.last_eq(b)), eq);
+                    assert_eq!(offset(&arr, arr.last_ne(b)), ne);
+                    assert_eq!(arr.last_eq_mut(b).is_some(), eq.is_some());
+                    assert_eq!(arr.last_ne_mut(b).is_some(), ne.is_some());
+                });
+            })+ };
+        }
 
+        test! { 2 4 8 16 32 }
+    }
+
+    #[test]
+    fn large_array() {
+        macro_rules! test {
+            ($($n:expr)+) => { $({
+                let mut arr = [0u8; $n];
+                assert_eq!(arr.last_eq(1), None);
+                assert_eq!(arr.last_ne(0), None);
+
+                for &i in &[0, 1, $n / 2, $n - 9, $n - 1] {
+                    arr[i] = 1;
+                    assert_eq!(arr.position_eq(1), Some(i));
+                    assert_eq!(arr.position_ne(0), Some(i));
+                    assert_eq!(arr.rposition_eq(1), Some(i));
+                    assert_eq!(arr.rposition_ne(0), Some(i));
+                    assert_eq!(offset(&arr, arr.last_eq(1)), Some(i));
+                    assert_eq!(offset(&arr, arr.last_ne(0)), Some(i));
+                    *arr.last_ne_mut(0).unwrap() = 0;
+                    assert!(arr.is_zero());
+                }
+            })+ };
+        }
+
+        test! { 64 128 192 448 768 1024 1792 7168 }
+    }
+
+    #[test]
+    fn scalar() {
+        let mut rng = thread_rng();
+
+        macro_rules! test {
+            ($($t:ident)+) => { $({
+                let val: $t = rng.gen();
+                let arr = val.to_ne_bytes();
+                all_bytes!(|b| {
+                    assert_eq!(val.position_eq(b),
+                               arr.iter().position(|&x| x == b));
+                    assert_eq!(val.position_ne(b),
+                               arr.iter().position(|&x| x != b));
+
+                    let eq = arr.iter().rposition(|&x| x == b);
+                    let ne = arr.iter().rposition(|&x| x != b);
+                    assert_eq!(val.rposition_eq(b), eq);
+                    assert_eq!(val.rposition_ne(b), ne);
+                    assert_eq!(offset(&val, val.last_eq(b)), eq);
+                    assert_eq!(offset(&val, val.last_ne(b)), ne);
+                });
+            })+ };
+        }
+
+        test! { u16 i16 u32 i32 u64 i64 usize isize }
+    }
+
+    #[cfg(feature = "portable_simd")]
+    #[test]
+    fn simd() {
         let mut rng = thread_rng();
 
         macro_rules! test {
             ($($n:expr => $s:ident,)+) => { $({
                 let arr: [u8; $n] = rng.gen();
-                let val: $s = unsafe { mem::transmute(arr) };
+                let val = $s::from_array(arr);
                 all_bytes!(|b| {
                     assert_eq!(val.contains(b), arr[..].contains(&b));
                     assert_eq!(val.is(b),       arr == [b; $n]);
@@ -298,7 +1675,7 @@ mod tests {
             })+ };
         }
 
-        // Test up to 32 due to current trait system
+        // Test up to 32 due to `rand` only generating arrays that long
         test! {
             2  => u8x2,
             4  => u8x4,
@@ -309,6 +1686,551 @@ mod tests {
         }
     }
 
+    #[test]
+    fn mask() {
+        use core::mem::size_of;
+
+        let mut rng = thread_rng();
+
+        // Few distinct bytes make for masks with both set and unset bits
+        let mut bytes = [0u8; 64];
+        for byte in bytes.iter_mut() {
+            *byte = rng.gen_range(0, 3);
+        }
+
+        // Returns the mask with bit `i` set if `f` holds for `bytes[i]`
+        fn expected<F: Fn(u8) -> bool>(bytes: &[u8], f: F) -> u64 {
+            bytes.iter().enumerate().fold(0, |mask, (i, &b)| {
+                mask | (f(b) as u64) << i
+            })
+        }
+
+        macro_rules! test {
+            ($($t:ty => $n:expr, $from:expr;)+) => { $({
+                let mut arr = [0u8; $n];
+                arr.copy_from_slice(&bytes[..$n]);
+                let val: $t = $from(arr);
+                all_bytes!(|b| {
+                    assert_eq!(val.eq_mask(b) as u64,
+                               expected(&arr, |x| x == b));
+                    assert_eq!(val.ne_mask(b) as u64,
+                               expected(&arr, |x| x != b));
+                });
+            })+ };
+        }
+
+        test! {
+            u8       => 1,  |x: [u8; 1]| x[0];
+            u16      => 2,  u16::from_ne_bytes;
+            i32      => 4,  i32::from_ne_bytes;
+            u64      => 8,  u64::from_ne_bytes;
+            usize    => size_of::<usize>(), usize::from_ne_bytes;
+            [u8; 2]  => 2,  |x| x;
+            [u8; 4]  => 4,  |x| x;
+            [u8; 8]  => 8,  |x| x;
+            [u8; 16] => 16, |x| x;
+            [u8; 32] => 32, |x| x;
+            [u8; 64] => 64, |x| x;
+        }
+    }
+
+    #[test]
+    fn slice_first() {
+        let mut rng = thread_rng();
+        let mut array = [0u8; 100];
+
+        for len in 0..array.len() {
+            // Use few distinct bytes so that matches occur at every offset
+            for byte in array.iter_mut() {
+                *byte = rng.gen_range(0, 4);
+            }
+
+            for start in 0..8 {
+                let slice = &mut array[start.min(len)..len];
+                for b in 0..5 {
+                    let eq = slice.iter().position(|&x| x == b);
+                    let ne = slice.iter().position(|&x| x != b);
+
+                    assert_eq!(slice.first_eq(b).map(|x| x as *const u8),
+                               eq.map(|i| &slice[i] as *const u8));
+                    assert_eq!(slice.first_ne(b).map(|x| x as *const u8),
+                               ne.map(|i| &slice[i] as *const u8));
+                    assert_eq!(slice.first_eq_mut(b).map(|x| *x),
+                               eq.map(|i| slice[i]));
+                    assert_eq!(slice.first_ne_mut(b).map(|x| *x),
+                               ne.map(|i| slice[i]));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn slice_positions() {
+        let mut rng = thread_rng();
+        let mut array = [0u8; 100];
+
+        for len in 0..array.len() {
+            // Use few distinct bytes so that matches occur at every offset
+            for byte in array.iter_mut() {
+                *byte = rng.gen_range(0, 4);
+            }
+
+            for start in 0..8 {
+                let slice = &mut array[start.min(len)..len];
+                for b in 0..5 {
+                    let eq = slice.iter().position(|&x| x == b);
+                    let ne = slice.iter().position(|&x| x != b);
+                    assert_eq!(slice.position_eq(b), eq);
+                    assert_eq!(slice.position_ne(b), ne);
+
+                    let eq = slice.iter().rposition(|&x| x == b);
+                    let ne = slice.iter().rposition(|&x| x != b);
+                    assert_eq!(slice.rposition_eq(b), eq);
+                    assert_eq!(slice.rposition_ne(b), ne);
+                    assert_eq!(offset(slice, slice.last_eq(b)), eq);
+                    assert_eq!(offset(slice, slice.last_ne(b)), ne);
+                    assert_eq!(slice.last_eq_mut(b).map(|x| *x),
+                               eq.map(|i| slice[i]));
+                    assert_eq!(slice.last_ne_mut(b).map(|x| *x),
+                               ne.map(|i| slice[i]));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn byte_set() {
+        let mut rng = thread_rng();
+
+        assert_eq!(ByteSet::from_range(0..=255), ByteSet::FULL);
+        assert_eq!(ByteSet::FULL.complement(), ByteSet::EMPTY);
+        assert_eq!(ByteSet::from_range(250..=255).len(), 6);
+        assert_eq!(ByteSet::from_bytes(b"abcabc").len(), 3);
+
+        let mut array = [0u8; 1024];
+        for byte in array.iter_mut() {
+            *byte = rng.gen();
+        }
+
+        // The matrix only uses sets of two bytes, so also try large ones
+        for _ in 0..32 {
+            let set: ByteSet = (0..rng.gen_range(0, 64)).map(|_| rng.gen())
+                                                         .collect();
+            for b in 0..=255 {
+                assert_ne!(set.contains(b), set.complement().contains(b));
+            }
+
+            for set in &[set, set.complement(), ByteSet::FULL] {
+                let slice = &array[3..];
+                let first = slice.iter().position(|&x| set.contains(x));
+                let all = slice.iter().all(|&x| set.contains(x));
+                assert_eq!(offset(slice, slice.first_in(set)), first);
+                assert_eq!(slice.contains_in(set), first.is_some());
+                assert_eq!(slice.all_in(set), all);
+            }
+        }
+    }
+
+    #[test]
+    fn count() {
+        let mut rng = thread_rng();
+
+        // Long enough for per-byte sums to be flushed several times, which
+        // the matrix's inputs are too short for
+        let mut vec = vec![0u8; 20_000];
+        assert_eq!(vec.count_eq(0), 20_000);
+        assert_eq!(vec[3..].count_ne(0), 0);
+
+        for byte in vec.iter_mut() {
+            *byte = rng.gen_range(0, 4);
+        }
+        for b in 0..5 {
+            let eq = vec.iter().filter(|&&x| x == b).count();
+            assert_eq!(vec[..].count_eq(b), eq);
+            assert_eq!(vec[..].count_ne(b), vec.len() - eq);
+        }
+    }
+
+    #[test]
+    fn ranges() {
+        let mut rng = thread_rng();
+        let vec: Vec<u8> = (0..100).map(|_| rng.gen()).collect();
+
+        // Bounds around the edges of the SWAR subtraction and comparison,
+        // which the matrix's ranges around each byte don't all reach
+        let bounds = [0, 1, 0x1F, 0x20, 0x7F, 0x80, 0x81, 0xFE, 0xFF];
+        for &lo in &bounds {
+            for &hi in &bounds {
+                let range = lo..=hi;
+                for chunk in vec.chunks(8) {
+                    let mut word = [lo; 8];
+                    word[..chunk.len()].copy_from_slice(chunk);
+                    let val = u64::from_ne_bytes(word);
+
+                    let first = word.iter().position(|x| range.contains(x));
+                    let all = word.iter().all(|x| range.contains(x));
+                    assert_eq!(offset(&val, val.first_in_range(lo..=hi)),
+                               first);
+                    assert_eq!(val.all_in_range(lo..=hi), all);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn utf8() {
+        use core::str;
+        use utf8::validate_utf8;
+
+        fn test(bytes: &[u8]) {
+            match (validate_utf8(bytes), str::from_utf8(bytes)) {
+                (Ok(a), Ok(b)) => assert_eq!(a.as_ptr(), b.as_ptr()),
+                (Err(a), Err(b)) => {
+                    assert_eq!(a.valid_up_to(), b.valid_up_to(), "{:?}", bytes);
+                    assert_eq!(a.error_len(), b.error_len(), "{:?}", bytes);
+                },
+                (a, b) => panic!("{:?}: {:?} != {:?}", bytes, a, b),
+            }
+        }
+
+        // Every sequence of up to two bytes, and of three or four bytes that
+        // start with a lead byte, around ASCII to put them at any offset
+        let mut seqs: Vec<Vec<u8>> = Vec::new();
+        for a in 0..=255 {
+            for b in 0..=255 {
+                seqs.push(vec![a, b]);
+            }
+        }
+        for a in 0xC0..=0xFF {
+            for &b in &[0x7F, 0x80, 0x8F, 0x90, 0x9F, 0xA0, 0xBF, 0xC0] {
+                for &c in &[0x41, 0x80, 0xBF, 0xC0] {
+                    seqs.push(vec![a, b]);
+                    seqs.push(vec![a, b, c]);
+                    seqs.push(vec![a, b, 0x80, c]);
+                }
+            }
+        }
+
+        let ascii = [b'x'; 40];
+        for seq in &seqs {
+            test(seq);
+            for &pad in &[1, 7, 31] {
+                test(&[&ascii[..pad], seq, &ascii[..pad]].concat());
+            }
+        }
+
+        // Valid text, then with each byte corrupted or the text cut short
+        let text = "ASCII then ünïcödé, then 日本語 and 🦀🦀, and ASCII again";
+        let mut bytes = text.as_bytes().to_vec();
+        test(&bytes);
+        for i in 0..bytes.len() {
+            test(&bytes[..i]);
+            test(&bytes[i..]);
+            for &x in &[0x00, 0x80, 0xBF, 0xC0, 0xE0, 0xF0, 0xFF] {
+                let old = bytes[i];
+                bytes[i] = x;
+                test(&bytes);
+                bytes[i] = old;
+            }
+        }
+    }
+
+    #[test]
+    fn ascii_case() {
+        let mut rng = thread_rng();
+
+        // Letters along with the bytes just outside of their ranges
+        let alphabet = b"@AMZ[`amz{ \x7F\x80\xC1\xE1\xFF";
+        let vec: Vec<u8> = (0..100).map(|_| {
+            alphabet[rng.gen_range(0, alphabet.len())]
+        }).collect();
+
+        for start in 0..16 {
+            for end in (start..start + 40).chain(vec![100]) {
+                let s = &vec[start..end];
+
+                let mut lower = s.to_vec();
+                let mut upper = s.to_vec();
+                AsciiCase::make_ascii_lowercase(&mut lower[..]);
+                AsciiCase::make_ascii_uppercase(&mut upper[..]);
+                assert_eq!(lower, s.to_ascii_lowercase());
+                assert_eq!(upper, s.to_ascii_uppercase());
+
+                assert!(AsciiCase::eq_ignore_ascii_case(s, &lower));
+                assert!(AsciiCase::eq_ignore_ascii_case(&upper[..], s));
+                let other = s.iter().position(|x| !x.is_ascii_alphabetic());
+                if let Some(i) = other {
+                    let mut other = lower.clone();
+                    other[i] ^= 0x20;
+                    assert!(!AsciiCase::eq_ignore_ascii_case(s, &other));
+                }
+
+                for &b in b"aZ[`\x80" {
+                    let eq = |x: &u8| x.eq_ignore_ascii_case(&b);
+                    assert_eq!(s.contains_ignore_ascii_case(b),
+                               s.iter().any(eq));
+                }
+            }
+        }
+
+        let mut arr = *b"Content-Type: text/HTML; charset=UTF-8";
+        arr.make_ascii_lowercase();
+        assert_eq!(&arr, b"content-type: text/html; charset=utf-8");
+        arr.make_ascii_uppercase();
+        assert_eq!(&arr, b"CONTENT-TYPE: TEXT/HTML; CHARSET=UTF-8");
+        let mixed = b"Content-type: text/HTML; Charset=utf-8";
+        assert!(arr.eq_ignore_ascii_case(mixed));
+    }
+
+    #[test]
+    fn translate() {
+        let mut rng = thread_rng();
+
+        let mut array = [0; 256];
+        rng.fill_bytes(&mut array);
+        let table = ByteTable::from(array)
+            .with(0, 0xFF)
+            .with_set(&ByteSet::from_range(b'0'..=b'9'), b'#');
+        let lookup = |s: &[u8]| -> Vec<u8> {
+            s.iter().map(|&b| table.get(b)).collect()
+        };
+
+        let vec: Vec<u8> = (0..200).map(|_| rng.gen()).collect();
+        let mut dst = [0; 200];
+
+        for start in 0..16 {
+            for end in (start..start + 80).chain(vec![200]) {
+                let s = &vec[start..end];
+
+                let mut copy = s.to_vec();
+                table.translate(&mut copy);
+                assert_eq!(copy, lookup(s));
+
+                // Offset the destination differently from the source
+                let dst = &mut dst[start / 2..start / 2 + s.len()];
+                table.translate_into(s, dst);
+                assert_eq!(dst, &lookup(s)[..]);
+            }
+        }
+
+        let all: Vec<u8> = (0..=255).collect();
+        let mut copy = all.clone();
+        super::translate(&mut copy, table.as_array());
+        assert_eq!(copy, lookup(&all));
+        ByteTable::IDENTITY.translate(&mut copy[..]);
+        assert_eq!(copy, lookup(&all));
+    }
+
+    #[test]
+    fn aligned_chunks() {
+        use core::mem;
+
+        fn test<B: Chunk>(bytes: &mut [u8]) {
+            let (size, align) = (mem::size_of::<B>(), mem::align_of::<B>());
+            let copy = bytes.to_vec();
+
+            let chunks = AlignedChunks::<B>::new(bytes);
+            let (x, y, z) = (chunks.prefix, chunks.batches, chunks.suffix);
+            assert_eq!(y.as_ptr() as usize % align, 0);
+            if size == 0 {
+                assert_eq!(x.len(), copy.len());
+            } else {
+                assert!(x.len() < align && z.len() < size);
+            }
+
+            let mid = unsafe {
+                core::slice::from_raw_parts(y.as_ptr() as *const u8,
+                                            y.len() * size)
+            };
+            assert_eq!([x, mid, z].concat(), copy);
+            let (prefix, mid) = (x.len(), mid.len());
+
+            let chunks = AlignedChunksMut::<B>::new(bytes);
+            assert_eq!(chunks.prefix.len(), prefix);
+            assert_eq!(chunks.batches.len() * size, mid);
+            for byte in chunks.prefix.iter_mut().chain(chunks.suffix) {
+                *byte = !*byte;
+            }
+            for batch in chunks.batches.iter_mut() {
+                *batch = unsafe { mem::zeroed() };
+            }
+            for (i, &b) in bytes.iter().enumerate() {
+                let in_mid = i >= prefix && i < prefix + mid;
+                assert_eq!(b, if in_mid { 0 } else { !copy[i] });
+            }
+        }
+
+        let mut vec: Vec<u8> = (1..=100).collect();
+        for start in 0..16 {
+            for end in (start..start + 40).chain(vec![100]) {
+                let s = &mut vec[start..end];
+                test::<u8>(s);
+                test::<u16>(s);
+                test::<u64>(s);
+                test::<u128>(s);
+                test::<Batch>(s);
+                test::<[u8; 0]>(s);
+                test::<[u8; 3]>(s);
+            }
+        }
+    }
+
+    #[test]
+    fn batch_predicates() {
+        const LO: u64 = u64::MAX / 0xFF;
+        const HI: u64 = LO << 7;
+
+        let mut rng = thread_rng();
+        let mut vec: Vec<u8> = (0..200).map(|_| rng.gen_range(0x20, 0x80))
+                                       .collect();
+        vec[150] = 0x1F;
+        vec[170] = 0xC3;
+
+        for start in 0..16 {
+            for end in (start..start + 40).chain(vec![160, 200]) {
+                let s = &vec[start..end];
+                let chunks = AlignedChunks::<u64>::new(s);
+
+                // Masks of the bytes at or above 0x80 and below 0x20, which
+                // are exact up to their lowest set bit
+                let high = |b: u8| b >= 0x80;
+                let high_mask = |x: u64| u64::from_le(x) & HI;
+                let ctrl = |b: u8| b < 0x20;
+                let ctrl_mask = |x: u64| {
+                    let x = u64::from_le(x);
+                    x.wrapping_sub(LO * 0x20) & !x & HI
+                };
+
+                for &(byte, mask) in &[(&high as &dyn Fn(u8) -> bool,
+                                        &high_mask as &dyn Fn(u64) -> u64),
+                                       (&ctrl, &ctrl_mask)] {
+                    let batch = |x| match mask(x) {
+                        0 => None,
+                        m => Some(m.trailing_zeros() as usize / 8),
+                    };
+                    let first = s.iter().position(|&b| byte(b));
+                    assert_eq!(chunks.first_where(batch, byte), first);
+                    assert_eq!(chunks.any_batch(|x| mask(x) != 0, byte),
+                               first.is_some());
+                    assert_eq!(chunks.all_batch(|x| mask(x) == 0, |b| !byte(b)),
+                               first.is_none());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn elements() {
+        let mut rng = thread_rng();
+
+        macro_rules! test { ($($t:ident)+) => { $(
+            // Values that differ in a single byte or only in their high bit
+            let vals = [0, 1, 1 << ($t::BITS - 8), $t::MAX, $t::MAX >> 1];
+            let vec: Vec<$t> = (0..300).map(|_| vals[rng.gen_range(0, 4)])
+                                       .collect();
+
+            for &elem in &vals {
+                for start in 0..8 {
+                    for end in (start..start + 40).chain(vec![100, 300]) {
+                        let s = &vec[start..end];
+                        let i = s.iter().position(|&e| e == elem);
+                        let all = s.iter().all(|&e| e == elem);
+
+                        assert_eq!(Elements::is(s, elem), !s.is_empty() && all);
+                        assert_eq!(Elements::contains(s, elem), i.is_some());
+                        assert_eq!(s.first_eq(elem).map(|e| e as *const $t),
+                                   i.map(|i| &s[i] as *const $t));
+                        assert_eq!(s.count_eq(elem),
+                                   s.iter().filter(|&&e| e == elem).count());
+                    }
+                }
+
+                let same = vec![elem; 100];
+                assert!(Elements::is(&same[..], elem));
+                assert!(!Elements::is(&same[..], !elem));
+                assert_eq!(same[..].count_eq(elem), 100);
+            }
+        )+ } }
+
+        test! { u16 u32 u64 }
+    }
+
+    #[test]
+    fn positions() {
+        let mut rng = thread_rng();
+        let mut array = [0u8; 300];
+        for byte in array.iter_mut() {
+            *byte = rng.gen_range(0, 4);
+        }
+
+        // Alternate ends so that they meet at every offset, which the matrix
+        // doesn't do
+        let slice = &array[3..];
+        for b in 0..5 {
+            let eq: Vec<usize> = (0..slice.len())
+                .filter(|&i| slice[i] == b)
+                .collect();
+
+            let mut iter = slice.iter_eq(b);
+            let (mut lo, mut hi) = (0, eq.len());
+            while lo < hi {
+                if (lo + hi) % 2 == 0 {
+                    assert_eq!(iter.next(), Some(eq[lo]));
+                    lo += 1;
+                } else {
+                    hi -= 1;
+                    assert_eq!(iter.next_back(), Some(eq[hi]));
+                }
+            }
+            assert_eq!(iter.next(), None);
+            assert_eq!(iter.next_back(), None);
+        }
+
+        let val = 0x0102_0102u32.to_le();
+        assert_eq!(val.iter_eq(2).collect::<Vec<_>>(), [0, 2]);
+        assert_eq!([7u8; 128].iter_ne(7).next(), None);
+    }
+
+    #[test]
+    fn find() {
+        let mut rng = thread_rng();
+        let mut array = [0u8; 300];
+        for byte in array.iter_mut() {
+            *byte = rng.gen_range(0, 3);
+        }
+
+        fn naive(haystack: &[u8], needle: &[u8]) -> (Option<usize>,
+                                                       Option<usize>) {
+            if needle.is_empty() {
+                return (Some(0), Some(haystack.len()));
+            }
+            let windows = || haystack.windows(needle.len());
+            (windows().position(|w| w == needle),
+             windows().rposition(|w| w == needle))
+        }
+
+        // The matrix only searches for needles of up to three bytes
+        let haystack = &array[3..];
+        for n in 0..12 {
+            let i = rng.gen_range(0, 300 - n);
+            let random: Vec<u8> = (0..n).map(|_| rng.gen_range(0, 3))
+                                        .collect();
+            for needle in &[&array[i..i + n], &random[..]] {
+                let (first, last) = naive(haystack, needle);
+                assert_eq!(haystack.find(needle), first);
+                assert_eq!(haystack.rfind(needle), last);
+                assert_eq!(haystack.contains_seq(needle), first.is_some());
+            }
+        }
+
+        let req = b"GET /index.html HTTP/1.1\r\nHost: x\r\n\r\n";
+        assert_eq!(req.find(b"\r\n"), Some(24));
+        assert_eq!(req.rfind(b"\r\n"), Some(35));
+        assert!(!req.contains_seq(b"POST"));
+        assert_eq!(u32::from_ne_bytes(*b"abcd").find(b"bc"), Some(1));
+        assert_eq!([0u8; 128].rfind(&[0; 64]), Some(64));
+    }
+
     #[test]
     fn slice() {
         const UNALIGNED: usize = 27;
@@ -1,154 +1,309 @@
-#[cfg(not(feature = "simd"))]
+#[cfg(not(feature = "portable_simd"))]
 use core::mem;
 
 use super::*;
 
+// Returns the compact mask of `$arr` made from the batch masks from `$f`.
+macro_rules! mask {
+    ($arr:expr, $n:expr, $s:ident, $f:ident($byte:expr)) => {{
+        #[cfg(feature = "portable_simd")]
+        { $s::from_array(*$arr).$f($byte) }
+
+        #[cfg(not(feature = "portable_simd"))]
+        {
+            const SIZE: usize = mem::size_of::<usize>();
+
+            let words: [usize; $n / SIZE] = unsafe { mem::transmute(*$arr) };
+            words.iter().enumerate().fold(0, |mask, (i, &word)| {
+                mask | compact(word.$f($byte)) << (i * SIZE)
+            })
+        }
+    }};
+}
+
 // Implementations for multiples of `mem::size_of::<usize>()`
 macro_rules! impl_bytes_multi {
-    ($($n:expr => $s:ident,)+) => { $(
-        impl SizedBytes for [u8; $n] {
-            #[inline]
-            fn splat(byte: u8) -> Self { [byte; $n] }
-        }
+    ($($n:expr => $s:ident $m:ident,)+) => { $(
+        impl MaskBytes for [u8; $n] {
+            type Mask = $m;
 
-        impl Bytes for [u8; $n] {
             #[inline]
-            fn is(&self, byte: u8) -> bool {
-                #[cfg(feature = "simd")]
-                { $s::load_unaligned(self).is(byte) }
-
-                #[cfg(not(feature = "simd"))]
-                {
-                    type Arr = [usize; $n / mem::size_of::<usize>()];
-                    let arr: Arr = unsafe { mem::transmute(*self) };
-
-                    for word in arr.iter() {
-                        if !word.is(byte) {
-                            return false;
-                        }
-                    }
-                    true
-                }
+            fn eq_mask(&self, byte: u8) -> $m {
+                mask!(self, $n, $s, eq_bits(byte)) as $m
             }
 
             #[inline]
-            fn contains(&self, byte: u8) -> bool {
-                #[cfg(feature = "simd")]
-                { $s::load_unaligned(self).contains(byte) }
-
-                #[cfg(not(feature = "simd"))]
-                {
-                    type Arr = [usize; $n / mem::size_of::<usize>()];
-                    let arr: Arr = unsafe { mem::transmute(*self) };
-
-                    for word in arr.iter() {
-                        if word.contains(byte) {
-                            return true;
-                        }
-                    }
-                    false
-                }
+            fn ne_mask(&self, byte: u8) -> $m {
+                mask!(self, $n, $s, ne_bits(byte)) as $m
             }
+        }
 
+        #[cfg(feature = "portable_simd")]
+        impl SizedBytes for $s {
             #[inline]
-            fn first_eq(&self, byte: u8) -> Option<&u8> {
-                unimplemented!()
+            fn splat(byte: u8) -> Self { Self::splat(byte) }
+        }
+
+        #[cfg(feature = "portable_simd")]
+        impl Matches for $s {
+            const STRIDE: u32 = 1;
+
+            #[inline]
+            fn eq_bits(self, byte: u8) -> u64 {
+                self.simd_eq(Self::splat(byte)).to_bitmask()
             }
 
             #[inline]
-            fn first_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
-                unimplemented!()
+            fn ne_bits(self, byte: u8) -> u64 {
+                self.simd_ne(Self::splat(byte)).to_bitmask()
             }
 
+            // Looks up the row of each byte by its low nibble with a
+            // lane-wise shuffle of the table half selected by its top bit,
+            // then tests the row's bit for the byte's remaining 3 bits
             #[inline]
-            fn first_ne(&self, byte: u8) -> Option<&u8> {
-                unimplemented!()
+            fn in_bits(self, set: &ByteSet) -> u64 {
+                let table = set.table();
+                let half = |h: usize| {
+                    let mut arr = [0u8; $n];
+                    for (i, x) in arr.iter_mut().enumerate() {
+                        *x = table[h * 16 + i % 16];
+                    }
+                    Self::from_array(arr)
+                };
+
+                let nibbles = self & Self::splat(0xF);
+                let lo = half(0).swizzle_dyn(nibbles);
+                let hi = half(1).swizzle_dyn(nibbles);
+                let rows = self.simd_ge(Self::splat(0x80)).select(hi, lo);
+
+                let bits = Self::splat(1) << ((self >> 4) & Self::splat(7));
+                (rows & bits).simd_ne(Self::splat(0)).to_bitmask()
             }
 
+            // Lanes wrap on subtraction, so one unsigned compare suffices
             #[inline]
-            fn first_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
-                unimplemented!()
+            fn range_bits(self, range: ByteRange) -> u64 {
+                let d = self - Self::splat(range.lo);
+                d.simd_le(Self::splat(range.span)).to_bitmask()
             }
 
+            // The sign bits, as with `movemask`
             #[inline]
-            fn last_eq(&self, byte: u8) -> Option<&u8> {
-                unimplemented!()
+            fn high_bits(self) -> u64 {
+                self.cast::<i8>().is_negative().to_bitmask()
             }
+        }
 
+        #[cfg(feature = "portable_simd")]
+        impl Rewrite for $s {
             #[inline]
-            fn last_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
-                unimplemented!()
+            fn flip_in_range(self, range: ByteRange, bits: u8) -> Self {
+                let d = self - Self::splat(range.lo);
+                let m = d.simd_le(Self::splat(range.span));
+                self ^ m.select(Self::splat(bits), Self::splat(0))
             }
 
             #[inline]
-            fn last_ne(&self, byte: u8) -> Option<&u8> {
-                unimplemented!()
+            fn replace(self, from: u8, to: u8) -> Self {
+                self.simd_eq(Self::splat(from)).select(Self::splat(to), self)
             }
 
             #[inline]
-            fn last_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
-                unimplemented!()
+            fn replace_in(self, set: &ByteSet, to: u8) -> Self {
+                let m = Mask::<i8, $n>::from_bitmask(self.in_bits(set));
+                m.select(Self::splat(to), self)
             }
         }
 
-        #[cfg(feature = "simd")]
-        impl SizedBytes for $s {
+        #[cfg(feature = "portable_simd")]
+        impl MaskBytes for $s {
+            type Mask = $m;
+
             #[inline]
-            fn splat(byte: u8) -> Self { Self::splat(byte) }
+            fn eq_mask(&self, byte: u8) -> $m { self.eq_bits(byte) as $m }
+
+            #[inline]
+            fn ne_mask(&self, byte: u8) -> $m { self.ne_bits(byte) as $m }
         }
 
-        #[cfg(feature = "simd")]
+        #[cfg(feature = "portable_simd")]
         impl Bytes for $s {
             #[inline]
             fn is(&self, byte: u8) -> bool {
-                (*self).eq(Self::splat(byte)).all()
+                self.simd_eq(Self::splat(byte)).all()
             }
 
             #[inline]
             fn contains(&self, byte: u8) -> bool {
-                (*self).eq(Self::splat(byte)).any()
+                self.simd_eq(Self::splat(byte)).any()
             }
 
             #[inline]
-            fn first_eq(&self, byte: u8) -> Option<&u8> {
-                unimplemented!()
+            fn contains_any2(&self, a: u8, b: u8) -> bool {
+                let x = *self;
+                (x.simd_eq(Self::splat(a)) | x.simd_eq(Self::splat(b))).any()
             }
 
             #[inline]
-            fn first_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
-                unimplemented!()
+            fn contains_any3(&self, a: u8, b: u8, c: u8) -> bool {
+                let x = *self;
+                let m = x.simd_eq(Self::splat(a)) | x.simd_eq(Self::splat(b));
+                (m | x.simd_eq(Self::splat(c))).any()
             }
 
             #[inline]
-            fn first_ne(&self, byte: u8) -> Option<&u8> {
-                unimplemented!()
+            fn first_eq(&self, byte: u8) -> Option<&u8> {
+                let i = self.position_eq(byte)?;
+                Some(unsafe { byte_at(self, i) })
             }
 
             #[inline]
-            fn first_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
-                unimplemented!()
+            fn first_ne(&self, byte: u8) -> Option<&u8> {
+                let i = self.position_ne(byte)?;
+                Some(unsafe { byte_at(self, i) })
             }
 
             #[inline]
             fn last_eq(&self, byte: u8) -> Option<&u8> {
-                unimplemented!()
+                let i = self.rposition_eq(byte)?;
+                Some(unsafe { byte_at(self, i) })
             }
 
             #[inline]
-            fn last_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
-                unimplemented!()
+            fn last_ne(&self, byte: u8) -> Option<&u8> {
+                let i = self.rposition_ne(byte)?;
+                Some(unsafe { byte_at(self, i) })
             }
 
             #[inline]
-            fn last_ne(&self, byte: u8) -> Option<&u8> {
-                unimplemented!()
+            fn position_eq(&self, byte: u8) -> Option<usize> {
+                first_pos::<Self>(self.eq_bits(byte))
             }
 
             #[inline]
-            fn last_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
-                unimplemented!()
+            fn position_ne(&self, byte: u8) -> Option<usize> {
+                first_pos::<Self>(self.ne_bits(byte))
+            }
+
+            #[inline]
+            fn rposition_eq(&self, byte: u8) -> Option<usize> {
+                last_pos::<Self>(self.eq_bits(byte))
+            }
+
+            #[inline]
+            fn rposition_ne(&self, byte: u8) -> Option<usize> {
+                last_pos::<Self>(self.ne_bits(byte))
+            }
+
+            #[inline]
+            fn first_any2(&self, a: u8, b: u8) -> Option<&u8> {
+                let mask = self.eq_bits(a) | self.eq_bits(b);
+                let i = first_pos::<Self>(mask)?;
+                Some(unsafe { byte_at(self, i) })
+            }
+
+            #[inline]
+            fn first_any3(&self, a: u8, b: u8, c: u8) -> Option<&u8> {
+                let mask = self.eq_bits(a) | self.eq_bits(b) | self.eq_bits(c);
+                let i = first_pos::<Self>(mask)?;
+                Some(unsafe { byte_at(self, i) })
+            }
+
+            #[inline]
+            fn first_in(&self, set: &ByteSet) -> Option<&u8> {
+                let i = first_pos::<Self>(self.in_bits(set))?;
+                Some(unsafe { byte_at(self, i) })
+            }
+
+            #[inline]
+            fn first_non_ascii(&self) -> Option<&u8> {
+                let i = first_pos::<Self>(self.high_bits())?;
+                Some(unsafe { byte_at(self, i) })
+            }
+
+            #[inline]
+            fn all_in_range(&self, range: RangeInclusive<u8>) -> bool {
+                all_in_range(range, |r| self.range_bits(r) != 0)
+            }
+
+            #[inline]
+            fn first_in_range(&self, range: RangeInclusive<u8>) -> Option<&u8> {
+                let mask = self.range_bits(ByteRange::new(range)?);
+                let i = first_pos::<Self>(mask)?;
+                Some(unsafe { byte_at(self, i) })
+            }
+
+            #[inline]
+            fn count_eq(&self, byte: u8) -> usize {
+                self.eq_bits(byte).count_ones() as usize
+            }
+
+            #[inline]
+            fn count_ne(&self, byte: u8) -> usize {
+                self.ne_bits(byte).count_ones() as usize
+            }
+
+            #[inline]
+            fn iter_eq(&self, byte: u8) -> Positions<'_> {
+                Positions::new(unsafe { as_bytes(self) }, byte, true)
+            }
+
+            #[inline]
+            fn iter_ne(&self, byte: u8) -> Positions<'_> {
+                Positions::new(unsafe { as_bytes(self) }, byte, false)
+            }
+
+            #[inline]
+            fn find(&self, needle: &[u8]) -> Option<usize> {
+                search::find(unsafe { as_bytes(self) }, needle)
+            }
+
+            #[inline]
+            fn rfind(&self, needle: &[u8]) -> Option<usize> {
+                search::rfind(unsafe { as_bytes(self) }, needle)
             }
          }
+
+        #[cfg(feature = "portable_simd")]
+        impl BytesMut for $s {
+            #[inline]
+            fn first_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
+                let i = self.position_eq(byte)?;
+                Some(unsafe { byte_at_mut(self, i) })
+            }
+
+            #[inline]
+            fn first_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
+                let i = self.position_ne(byte)?;
+                Some(unsafe { byte_at_mut(self, i) })
+            }
+
+            #[inline]
+            fn last_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
+                let i = self.rposition_eq(byte)?;
+                Some(unsafe { byte_at_mut(self, i) })
+            }
+
+            #[inline]
+            fn last_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
+                let i = self.rposition_ne(byte)?;
+                Some(unsafe { byte_at_mut(self, i) })
+            }
+
+            #[inline]
+            fn fill(&mut self, byte: u8) { *self = Self::splat(byte) }
+
+            #[inline]
+            fn replace(&mut self, from: u8, to: u8) {
+                *self = Rewrite::replace(*self, from, to);
+            }
+
+            #[inline]
+            fn replace_all_in(&mut self, set: &ByteSet, to: u8) {
+                *self = self.replace_in(set, to);
+            }
+        }
     )+ }
 }
 
@@ -156,7 +311,7 @@ impl_bytes_multi! {
     // 2  => u8x2,
     // 4  => u8x4,
     // 8  => u8x8,
-    16 => u8x16,
-    32 => u8x32,
-    64 => u8x64,
+    16 => u8x16 u16,
+    32 => u8x32 u32,
+    64 => u8x64 u64,
 }
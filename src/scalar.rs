@@ -1,67 +1,203 @@
+use core::num::*;
+
 use super::*;
 
-const LO: u64 = ::core::u64::MAX / 0xFF;
-const HI: u64 = LO << 7;
+const LO: u128 = u128::MAX / 0xFF;
+const HI: u128 = LO << 7;
+
+// Word-at-a-time checks shared by every integer width
+macro_rules! swar_bytes {
+    () => {
+        #[inline]
+        fn is(&self, byte: u8) -> bool { *self == Self::splat(byte) }
+
+        #[inline]
+        fn contains(&self, byte: u8) -> bool {
+            (self ^ Self::splat(byte)).contains_zero()
+        }
+
+        // From Matters Computational by J. Arndt (1.20)
+        //
+        // "The idea is to subtract one from each of the bytes and then look
+        // for bytes where the borrow propagated all the way to the most
+        // significant bit."
+        #[inline]
+        fn contains_zero(&self) -> bool {
+            self.wrapping_sub(LO as Self) & !self & HI as Self != 0
+        }
+
+        #[inline]
+        fn is_ascii(&self) -> bool { self & HI as Self == 0 }
+
+        #[inline]
+        fn contains_any2(&self, a: u8, b: u8) -> bool {
+            (self ^ Self::splat(a)).contains_zero() ||
+            (self ^ Self::splat(b)).contains_zero()
+        }
+
+        #[inline]
+        fn contains_any3(&self, a: u8, b: u8, c: u8) -> bool {
+            (self ^ Self::splat(a)).contains_zero() ||
+            (self ^ Self::splat(b)).contains_zero() ||
+            (self ^ Self::splat(c)).contains_zero()
+        }
+    };
+}
 
 macro_rules! impl_bytes_scalar {
     ($($t:ident $u:ident)+) => { $(
         impl Bytes for $t {
+            swar_bytes!();
+
             #[inline]
-            fn is(&self, byte: u8) -> bool { *self == Self::splat(byte) }
+            fn first_eq(&self, byte: u8) -> Option<&u8> {
+                let i = self.position_eq(byte)?;
+                Some(unsafe { byte_at(self, i) })
+            }
 
             #[inline]
-            fn contains(&self, byte: u8) -> bool {
-                (self ^ Self::splat(byte)).contains_zero()
+            fn first_ne(&self, byte: u8) -> Option<&u8> {
+                let i = self.position_ne(byte)?;
+                Some(unsafe { byte_at(self, i) })
             }
 
-            // From Matters Computational by J. Arndt (1.20)
-            //
-            // "The idea is to subtract one from each of the bytes and then look
-            // for bytes where the borrow propagated all the way to the most
-            // significant bit."
             #[inline]
-            fn contains_zero(&self) -> bool {
-                self.wrapping_sub(LO as Self) & !self & HI as Self != 0
+            fn last_eq(&self, byte: u8) -> Option<&u8> {
+                let i = self.rposition_eq(byte)?;
+                Some(unsafe { byte_at(self, i) })
             }
 
             #[inline]
-            fn first_eq(&self, byte: u8) -> Option<&u8> {
-                unimplemented!()
+            fn last_ne(&self, byte: u8) -> Option<&u8> {
+                let i = self.rposition_ne(byte)?;
+                Some(unsafe { byte_at(self, i) })
             }
 
             #[inline]
-            fn first_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
-                unimplemented!()
+            fn position_eq(&self, byte: u8) -> Option<usize> {
+                first_pos::<Self>(self.eq_bits(byte))
             }
 
             #[inline]
-            fn first_ne(&self, byte: u8) -> Option<&u8> {
-                unimplemented!()
+            fn position_ne(&self, byte: u8) -> Option<usize> {
+                first_pos::<Self>(self.ne_bits(byte))
             }
 
             #[inline]
-            fn first_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
-                unimplemented!()
+            fn rposition_eq(&self, byte: u8) -> Option<usize> {
+                last_pos::<Self>(self.eq_bits(byte))
             }
 
             #[inline]
-            fn last_eq(&self, byte: u8) -> Option<&u8> {
-                unimplemented!()
+            fn rposition_ne(&self, byte: u8) -> Option<usize> {
+                last_pos::<Self>(self.ne_bits(byte))
             }
 
             #[inline]
-            fn last_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
-                unimplemented!()
+            fn first_any2(&self, a: u8, b: u8) -> Option<&u8> {
+                let mask = self.eq_bits(a) | self.eq_bits(b);
+                let i = first_pos::<Self>(mask)?;
+                Some(unsafe { byte_at(self, i) })
             }
 
             #[inline]
-            fn last_ne(&self, byte: u8) -> Option<&u8> {
-                unimplemented!()
+            fn first_any3(&self, a: u8, b: u8, c: u8) -> Option<&u8> {
+                let mask = self.eq_bits(a) | self.eq_bits(b) | self.eq_bits(c);
+                let i = first_pos::<Self>(mask)?;
+                Some(unsafe { byte_at(self, i) })
+            }
+
+            #[inline]
+            fn first_in(&self, set: &ByteSet) -> Option<&u8> {
+                let i = first_pos::<Self>(self.in_bits(set))?;
+                Some(unsafe { byte_at(self, i) })
+            }
+
+            #[inline]
+            fn first_non_ascii(&self) -> Option<&u8> {
+                let i = first_pos::<Self>(self.high_bits())?;
+                Some(unsafe { byte_at(self, i) })
+            }
+
+            #[inline]
+            fn all_in_range(&self, range: RangeInclusive<u8>) -> bool {
+                all_in_range(range, |r| self.range_bits(r) != 0)
+            }
+
+            #[inline]
+            fn first_in_range(&self, range: RangeInclusive<u8>) -> Option<&u8> {
+                let mask = self.range_bits(ByteRange::new(range)?);
+                let i = first_pos::<Self>(mask)?;
+                Some(unsafe { byte_at(self, i) })
+            }
+
+            #[inline]
+            fn count_eq(&self, byte: u8) -> usize {
+                self.eq_bits(byte).count_ones() as usize
+            }
+
+            #[inline]
+            fn count_ne(&self, byte: u8) -> usize {
+                self.ne_bits(byte).count_ones() as usize
+            }
+
+            #[inline]
+            fn iter_eq(&self, byte: u8) -> Positions<'_> {
+                Positions::new(unsafe { as_bytes(self) }, byte, true)
+            }
+
+            #[inline]
+            fn iter_ne(&self, byte: u8) -> Positions<'_> {
+                Positions::new(unsafe { as_bytes(self) }, byte, false)
+            }
+
+            #[inline]
+            fn find(&self, needle: &[u8]) -> Option<usize> {
+                search::find(unsafe { as_bytes(self) }, needle)
+            }
+
+            #[inline]
+            fn rfind(&self, needle: &[u8]) -> Option<usize> {
+                search::rfind(unsafe { as_bytes(self) }, needle)
+            }
+        }
+
+        impl BytesMut for $t {
+            #[inline]
+            fn first_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
+                let i = self.position_eq(byte)?;
+                Some(unsafe { byte_at_mut(self, i) })
+            }
+
+            #[inline]
+            fn first_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
+                let i = self.position_ne(byte)?;
+                Some(unsafe { byte_at_mut(self, i) })
+            }
+
+            #[inline]
+            fn last_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
+                let i = self.rposition_eq(byte)?;
+                Some(unsafe { byte_at_mut(self, i) })
             }
 
             #[inline]
             fn last_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
-                unimplemented!()
+                let i = self.rposition_ne(byte)?;
+                Some(unsafe { byte_at_mut(self, i) })
+            }
+
+            #[inline]
+            fn fill(&mut self, byte: u8) { *self = Self::splat(byte) }
+
+            #[inline]
+            fn replace(&mut self, from: u8, to: u8) {
+                *self = Rewrite::replace(*self, from, to);
+            }
+
+            #[inline]
+            fn replace_all_in(&mut self, set: &ByteSet, to: u8) {
+                *self = self.replace_in(set, to);
             }
         }
 
@@ -76,46 +212,284 @@ macro_rules! impl_bytes_scalar {
             fn contains_zero(&self) -> bool { (*self as $t).contains_zero() }
 
             #[inline]
-            fn first_eq(&self, byte: u8) -> Option<&u8> {
-                unimplemented!()
+            fn contains_any2(&self, a: u8, b: u8) -> bool {
+                (*self as $t).contains_any2(a, b)
             }
 
             #[inline]
-            fn first_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
-                unimplemented!()
+            fn contains_any3(&self, a: u8, b: u8, c: u8) -> bool {
+                (*self as $t).contains_any3(a, b, c)
             }
 
             #[inline]
-            fn first_ne(&self, byte: u8) -> Option<&u8> {
-                unimplemented!()
+            fn first_eq(&self, byte: u8) -> Option<&u8> {
+                let i = self.position_eq(byte)?;
+                Some(unsafe { byte_at(self, i) })
             }
 
             #[inline]
-            fn first_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
-                unimplemented!()
+            fn first_ne(&self, byte: u8) -> Option<&u8> {
+                let i = self.position_ne(byte)?;
+                Some(unsafe { byte_at(self, i) })
             }
 
             #[inline]
             fn last_eq(&self, byte: u8) -> Option<&u8> {
-                unimplemented!()
+                let i = self.rposition_eq(byte)?;
+                Some(unsafe { byte_at(self, i) })
             }
 
             #[inline]
-            fn last_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
-                unimplemented!()
+            fn last_ne(&self, byte: u8) -> Option<&u8> {
+                let i = self.rposition_ne(byte)?;
+                Some(unsafe { byte_at(self, i) })
             }
 
             #[inline]
-            fn last_ne(&self, byte: u8) -> Option<&u8> {
-                unimplemented!()
+            fn position_eq(&self, byte: u8) -> Option<usize> {
+                first_pos::<Self>(self.eq_bits(byte))
+            }
+
+            #[inline]
+            fn position_ne(&self, byte: u8) -> Option<usize> {
+                first_pos::<Self>(self.ne_bits(byte))
+            }
+
+            #[inline]
+            fn rposition_eq(&self, byte: u8) -> Option<usize> {
+                last_pos::<Self>(self.eq_bits(byte))
+            }
+
+            #[inline]
+            fn rposition_ne(&self, byte: u8) -> Option<usize> {
+                last_pos::<Self>(self.ne_bits(byte))
+            }
+
+            #[inline]
+            fn first_any2(&self, a: u8, b: u8) -> Option<&u8> {
+                let mask = self.eq_bits(a) | self.eq_bits(b);
+                let i = first_pos::<Self>(mask)?;
+                Some(unsafe { byte_at(self, i) })
+            }
+
+            #[inline]
+            fn first_any3(&self, a: u8, b: u8, c: u8) -> Option<&u8> {
+                let mask = self.eq_bits(a) | self.eq_bits(b) | self.eq_bits(c);
+                let i = first_pos::<Self>(mask)?;
+                Some(unsafe { byte_at(self, i) })
+            }
+
+            #[inline]
+            fn first_in(&self, set: &ByteSet) -> Option<&u8> {
+                let i = first_pos::<Self>(self.in_bits(set))?;
+                Some(unsafe { byte_at(self, i) })
+            }
+
+            #[inline]
+            fn first_non_ascii(&self) -> Option<&u8> {
+                let i = first_pos::<Self>(self.high_bits())?;
+                Some(unsafe { byte_at(self, i) })
+            }
+
+            #[inline]
+            fn all_in_range(&self, range: RangeInclusive<u8>) -> bool {
+                all_in_range(range, |r| self.range_bits(r) != 0)
+            }
+
+            #[inline]
+            fn first_in_range(&self, range: RangeInclusive<u8>) -> Option<&u8> {
+                let mask = self.range_bits(ByteRange::new(range)?);
+                let i = first_pos::<Self>(mask)?;
+                Some(unsafe { byte_at(self, i) })
+            }
+
+            #[inline]
+            fn count_eq(&self, byte: u8) -> usize {
+                self.eq_bits(byte).count_ones() as usize
+            }
+
+            #[inline]
+            fn count_ne(&self, byte: u8) -> usize {
+                self.ne_bits(byte).count_ones() as usize
+            }
+
+            #[inline]
+            fn iter_eq(&self, byte: u8) -> Positions<'_> {
+                Positions::new(unsafe { as_bytes(self) }, byte, true)
+            }
+
+            #[inline]
+            fn iter_ne(&self, byte: u8) -> Positions<'_> {
+                Positions::new(unsafe { as_bytes(self) }, byte, false)
+            }
+
+            #[inline]
+            fn find(&self, needle: &[u8]) -> Option<usize> {
+                search::find(unsafe { as_bytes(self) }, needle)
+            }
+
+            #[inline]
+            fn rfind(&self, needle: &[u8]) -> Option<usize> {
+                search::rfind(unsafe { as_bytes(self) }, needle)
+            }
+        }
+
+        impl BytesMut for $u {
+            #[inline]
+            fn first_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
+                let i = self.position_eq(byte)?;
+                Some(unsafe { byte_at_mut(self, i) })
+            }
+
+            #[inline]
+            fn first_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
+                let i = self.position_ne(byte)?;
+                Some(unsafe { byte_at_mut(self, i) })
+            }
+
+            #[inline]
+            fn last_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
+                let i = self.rposition_eq(byte)?;
+                Some(unsafe { byte_at_mut(self, i) })
             }
 
             #[inline]
             fn last_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
-                unimplemented!()
+                let i = self.rposition_ne(byte)?;
+                Some(unsafe { byte_at_mut(self, i) })
+            }
+
+            #[inline]
+            fn fill(&mut self, byte: u8) { *self = $t::splat(byte) as $u }
+
+            #[inline]
+            fn replace(&mut self, from: u8, to: u8) {
+                *self = Rewrite::replace(*self as $t, from, to) as $u;
+            }
+
+            #[inline]
+            fn replace_all_in(&mut self, set: &ByteSet, to: u8) {
+                *self = (*self as $t).replace_in(set, to) as $u;
+            }
+        }
+
+        impl Matches for $t {
+            const STRIDE: u32 = 8;
+
+            // Unlike the borrow trick in `contains_zero`, adding to the low
+            // seven bits of each byte can't carry into its neighbors, so only
+            // the high bits of zero bytes are left set.
+            #[inline]
+            fn eq_bits(self, byte: u8) -> u64 {
+                const LOW: $t = !(HI as $t);
+
+                let x = $t::from_le(self) ^ Self::splat(byte);
+                !((x & LOW) + LOW | x | LOW) as u64
+            }
+
+            #[inline]
+            fn ne_bits(self, byte: u8) -> u64 {
+                self.eq_bits(byte) ^ HI as $t as u64
+            }
+
+            // Arbitrary sets have no word-wide trick, so look up each byte
+            #[inline]
+            fn in_bits(self, set: &ByteSet) -> u64 {
+                let bytes = self.to_ne_bytes();
+                bytes.iter().enumerate().fold(0, |mask, (i, &b)| {
+                    mask | (set.contains(b) as u64) << (i * 8 + 7)
+                })
+            }
+
+            // Subtracts `lo` from each byte, setting each high bit beforehand
+            // so that no borrow crosses into the next byte, then compares the
+            // differences with `span` by whether subtracting them from it
+            // would borrow out of a byte
+            #[inline]
+            fn range_bits(self, range: ByteRange) -> u64 {
+                const H: $t = HI as $t;
+
+                let x = $t::from_le(self);
+                let lo = Self::splat(range.lo);
+                let span = Self::splat(range.span);
+
+                let d = ((x | H) - (lo & !H)) ^ ((x ^ !lo) & H);
+                let low = (span | H) - (d & !H);
+                let borrow = !span & d | !(span ^ d) & !low;
+                (!borrow & H) as u64
+            }
+
+            #[inline]
+            fn high_bits(self) -> u64 { ($t::from_le(self) & HI as $t) as u64 }
+        }
+
+        impl Matches for $u {
+            const STRIDE: u32 = 8;
+
+            #[inline]
+            fn eq_bits(self, byte: u8) -> u64 { (self as $t).eq_bits(byte) }
+
+            #[inline]
+            fn ne_bits(self, byte: u8) -> u64 { (self as $t).ne_bits(byte) }
+
+            #[inline]
+            fn in_bits(self, set: &ByteSet) -> u64 { (self as $t).in_bits(set) }
+
+            #[inline]
+            fn range_bits(self, range: ByteRange) -> u64 {
+                (self as $t).range_bits(range)
+            }
+
+            #[inline]
+            fn high_bits(self) -> u64 { (self as $t).high_bits() }
+        }
+
+        impl Rewrite for $t {
+            #[inline]
+            fn flip_in_range(self, range: ByteRange, bits: u8) -> Self {
+                // A 1 in the low bit of each byte in `range`
+                let ones = self.range_bits(range) as $t >> 7;
+                self ^ $t::to_le(ones * bits as $t)
+            }
+
+            // Each matching byte is flipped into `to`
+            #[inline]
+            fn replace(self, from: u8, to: u8) -> Self {
+                let ones = self.eq_bits(from) as $t >> 7;
+                self ^ $t::to_le(ones * (from ^ to) as $t)
+            }
+
+            #[inline]
+            fn replace_in(self, set: &ByteSet, to: u8) -> Self {
+                let mask = $t::to_le((self.in_bits(set) as $t >> 7) * 0xFF);
+                self & !mask | Self::splat(to) & mask
+            }
+        }
+
+        impl MaskBytes for $t {
+            type Mask = u8;
+
+            #[inline]
+            fn eq_mask(&self, byte: u8) -> u8 {
+                compact(self.eq_bits(byte)) as u8
+            }
+
+            #[inline]
+            fn ne_mask(&self, byte: u8) -> u8 {
+                compact(self.ne_bits(byte)) as u8
             }
         }
 
+        impl MaskBytes for $u {
+            type Mask = u8;
+
+            #[inline]
+            fn eq_mask(&self, byte: u8) -> u8 { (*self as $t).eq_mask(byte) }
+
+            #[inline]
+            fn ne_mask(&self, byte: u8) -> u8 { (*self as $t).ne_mask(byte) }
+        }
+
         impl SizedBytes for $t {
             #[inline]
             fn splat(byte: u8) -> Self {
@@ -132,3 +506,201 @@ macro_rules! impl_bytes_scalar {
 }
 
 impl_bytes_scalar! { u16 i16 u32 i32 u64 i64 usize isize }
+
+// Returns the bytes of `val` in memory order.
+#[inline]
+fn bytes(val: &u128) -> &[u8; 16] { unsafe { cast_ref(val) } }
+
+// Returns the bytes of `val` in memory order.
+#[inline]
+fn bytes_mut(val: &mut u128) -> &mut [u8; 16] { unsafe { cast_mut(val) } }
+
+// `u128` is too wide for a `Matches` mask, so bytes are located through its
+// array of bytes instead
+impl Bytes for u128 {
+    swar_bytes!();
+
+    #[inline]
+    fn first_eq(&self, byte: u8) -> Option<&u8> { bytes(self).first_eq(byte) }
+
+    #[inline]
+    fn first_ne(&self, byte: u8) -> Option<&u8> { bytes(self).first_ne(byte) }
+
+    #[inline]
+    fn last_eq(&self, byte: u8) -> Option<&u8> { bytes(self).last_eq(byte) }
+
+    #[inline]
+    fn last_ne(&self, byte: u8) -> Option<&u8> { bytes(self).last_ne(byte) }
+
+    #[inline]
+    fn position_eq(&self, byte: u8) -> Option<usize> {
+        bytes(self).position_eq(byte)
+    }
+
+    #[inline]
+    fn position_ne(&self, byte: u8) -> Option<usize> {
+        bytes(self).position_ne(byte)
+    }
+
+    #[inline]
+    fn rposition_eq(&self, byte: u8) -> Option<usize> {
+        bytes(self).rposition_eq(byte)
+    }
+
+    #[inline]
+    fn rposition_ne(&self, byte: u8) -> Option<usize> {
+        bytes(self).rposition_ne(byte)
+    }
+
+    #[inline]
+    fn first_any2(&self, a: u8, b: u8) -> Option<&u8> {
+        bytes(self).first_any2(a, b)
+    }
+
+    #[inline]
+    fn first_any3(&self, a: u8, b: u8, c: u8) -> Option<&u8> {
+        bytes(self).first_any3(a, b, c)
+    }
+
+    #[inline]
+    fn first_in(&self, set: &ByteSet) -> Option<&u8> {
+        bytes(self).first_in(set)
+    }
+
+    #[inline]
+    fn first_non_ascii(&self) -> Option<&u8> { bytes(self).first_non_ascii() }
+
+    #[inline]
+    fn all_in_range(&self, range: RangeInclusive<u8>) -> bool {
+        bytes(self).all_in_range(range)
+    }
+
+    #[inline]
+    fn first_in_range(&self, range: RangeInclusive<u8>) -> Option<&u8> {
+        bytes(self).first_in_range(range)
+    }
+
+    #[inline]
+    fn count_eq(&self, byte: u8) -> usize { bytes(self).count_eq(byte) }
+
+    #[inline]
+    fn count_ne(&self, byte: u8) -> usize { bytes(self).count_ne(byte) }
+
+    #[inline]
+    fn iter_eq(&self, byte: u8) -> Positions<'_> { bytes(self).iter_eq(byte) }
+
+    #[inline]
+    fn iter_ne(&self, byte: u8) -> Positions<'_> { bytes(self).iter_ne(byte) }
+
+    #[inline]
+    fn find(&self, needle: &[u8]) -> Option<usize> { bytes(self).find(needle) }
+
+    #[inline]
+    fn rfind(&self, needle: &[u8]) -> Option<usize> {
+        bytes(self).rfind(needle)
+    }
+}
+
+impl BytesMut for u128 {
+    #[inline]
+    fn first_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
+        bytes_mut(self).first_eq_mut(byte)
+    }
+
+    #[inline]
+    fn first_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
+        bytes_mut(self).first_ne_mut(byte)
+    }
+
+    #[inline]
+    fn last_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
+        bytes_mut(self).last_eq_mut(byte)
+    }
+
+    #[inline]
+    fn last_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
+        bytes_mut(self).last_ne_mut(byte)
+    }
+
+    #[inline]
+    fn fill(&mut self, byte: u8) { *self = Self::splat(byte) }
+
+    #[inline]
+    fn replace(&mut self, from: u8, to: u8) {
+        bytes_mut(self).replace(from, to)
+    }
+
+    #[inline]
+    fn replace_all_in(&mut self, set: &ByteSet, to: u8) {
+        bytes_mut(self).replace_all_in(set, to)
+    }
+}
+
+impl MaskBytes for u128 {
+    type Mask = u16;
+
+    #[inline]
+    fn eq_mask(&self, byte: u8) -> u16 { bytes(self).eq_mask(byte) }
+
+    #[inline]
+    fn ne_mask(&self, byte: u8) -> u16 { bytes(self).ne_mask(byte) }
+}
+
+impl SizedBytes for u128 {
+    #[inline]
+    fn splat(byte: u8) -> Self { LO * byte as Self }
+}
+
+// These have the same layout as the integers they forward to
+impl_bytes_via! {
+    i8           => |x| unsafe { cast_ref::<_, u8>(x) },
+    i128         => |x| unsafe { cast_ref::<_, u128>(x) },
+    NonZeroU8    => |x| unsafe { cast_ref::<_, u8>(x) },
+    NonZeroI8    => |x| unsafe { cast_ref::<_, i8>(x) },
+    NonZeroU16   => |x| unsafe { cast_ref::<_, u16>(x) },
+    NonZeroI16   => |x| unsafe { cast_ref::<_, i16>(x) },
+    NonZeroU32   => |x| unsafe { cast_ref::<_, u32>(x) },
+    NonZeroI32   => |x| unsafe { cast_ref::<_, i32>(x) },
+    NonZeroU64   => |x| unsafe { cast_ref::<_, u64>(x) },
+    NonZeroI64   => |x| unsafe { cast_ref::<_, i64>(x) },
+    NonZeroU128  => |x| unsafe { cast_ref::<_, u128>(x) },
+    NonZeroI128  => |x| unsafe { cast_ref::<_, i128>(x) },
+    NonZeroUsize => |x| unsafe { cast_ref::<_, usize>(x) },
+    NonZeroIsize => |x| unsafe { cast_ref::<_, isize>(x) },
+}
+
+// Unlike the `NonZero` types, any bytes written to these are valid
+impl_bytes_mut_via! {
+    i8   => |x| unsafe { cast_mut::<_, u8>(x) },
+    i128 => |x| unsafe { cast_mut::<_, u128>(x) },
+}
+
+impl MaskBytes for i8 {
+    type Mask = u8;
+
+    #[inline]
+    fn eq_mask(&self, byte: u8) -> u8 { (*self as u8).eq_mask(byte) }
+
+    #[inline]
+    fn ne_mask(&self, byte: u8) -> u8 { (*self as u8).ne_mask(byte) }
+}
+
+impl SizedBytes for i8 {
+    #[inline]
+    fn splat(byte: u8) -> Self { byte as Self }
+}
+
+impl MaskBytes for i128 {
+    type Mask = u16;
+
+    #[inline]
+    fn eq_mask(&self, byte: u8) -> u16 { (*self as u128).eq_mask(byte) }
+
+    #[inline]
+    fn ne_mask(&self, byte: u8) -> u16 { (*self as u128).ne_mask(byte) }
+}
+
+impl SizedBytes for i128 {
+    #[inline]
+    fn splat(byte: u8) -> Self { u128::splat(byte) as Self }
+}
@@ -0,0 +1,96 @@
+use core::{mem, ptr};
+
+use super::*;
+
+const SIZE: usize = mem::size_of::<Batch>();
+
+// Reads the batch starting at `bytes[i]`, which may be unaligned.
+#[inline]
+unsafe fn load(bytes: &[u8], i: usize) -> Batch {
+    debug_assert!(i + SIZE <= bytes.len());
+    ptr::read_unaligned(bytes.as_ptr().add(i) as *const Batch)
+}
+
+// Returns the mask of offsets within the `SIZE` candidates starting at `i`
+// where both the first and last bytes of the needle are found.
+#[inline]
+unsafe fn candidates(haystack: &[u8], i: usize, needle: &[u8]) -> u64 {
+    let last = needle.len() - 1;
+    load(haystack, i).eq_bits(needle[0]) &
+    load(haystack, i + last).eq_bits(needle[last])
+}
+
+// Returns whether `needle` is at `haystack[i]`, given that its first and last
+// bytes already are.
+#[inline]
+fn is_match(haystack: &[u8], i: usize, needle: &[u8]) -> bool {
+    let mid = &needle[1..needle.len() - 1];
+    &haystack[i + 1..i + 1 + mid.len()] == mid
+}
+
+// Returns whether `needle`'s first and last bytes are at `haystack[i]`.
+#[inline]
+fn is_candidate(haystack: &[u8], i: usize, needle: &[u8]) -> bool {
+    haystack[i] == needle[0] &&
+    haystack[i + needle.len() - 1] == needle[needle.len() - 1]
+}
+
+// Returns the index of the first occurrence of `needle` in `haystack`.
+//
+// Rather than comparing the needle at every offset, a whole batch of offsets
+// is filtered down at once to those where its first and last bytes match.
+pub fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    match needle.len() {
+        0 => return Some(0),
+        1 => return haystack.position_eq(needle[0]),
+        n if n > haystack.len() => return None,
+        _ => {},
+    }
+
+    // The number of offsets at which `needle` may start
+    let end = haystack.len() - needle.len() + 1;
+
+    let mut i = 0;
+    while i + SIZE <= end {
+        let mut mask = unsafe { candidates(haystack, i, needle) };
+        while let Some(pos) = first_pos::<Batch>(mask) {
+            if is_match(haystack, i + pos, needle) {
+                return Some(i + pos);
+            }
+            mask &= mask - 1;
+        }
+        i += SIZE;
+    }
+
+    (i..end).find(|&i| {
+        is_candidate(haystack, i, needle) && is_match(haystack, i, needle)
+    })
+}
+
+// Returns the index of the last occurrence of `needle` in `haystack`.
+pub fn rfind(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    match needle.len() {
+        0 => return Some(haystack.len()),
+        1 => return haystack.rposition_eq(needle[0]),
+        n if n > haystack.len() => return None,
+        _ => {},
+    }
+
+    let mut end = haystack.len() - needle.len() + 1;
+
+    while end >= SIZE {
+        let i = end - SIZE;
+        let mut mask = unsafe { candidates(haystack, i, needle) };
+        while let Some(pos) = last_pos::<Batch>(mask) {
+            if is_match(haystack, i + pos, needle) {
+                return Some(i + pos);
+            }
+            mask ^= 1 << (63 - mask.leading_zeros());
+        }
+        end = i;
+    }
+
+    (0..end).rev().find(|&i| {
+        is_candidate(haystack, i, needle) && is_match(haystack, i, needle)
+    })
+}
@@ -0,0 +1,175 @@
+use core::fmt;
+use core::iter::FromIterator;
+use core::ops::RangeInclusive;
+
+/// A set of bytes, stored as a 256-bit bitmap.
+///
+/// Sets can be built in constant expressions:
+///
+/// ```
+/// use byte_ops::{ByteSet, Bytes};
+///
+/// const DELIMITERS: ByteSet = ByteSet::from_bytes(b",;\t");
+/// const HEX: ByteSet = ByteSet::from_range(b'0'..=b'9')
+///     .with_range(b'a'..=b'f')
+///     .with_range(b'A'..=b'F');
+///
+/// assert_eq!(b"name;value".first_in(&DELIMITERS), Some(&b';'));
+/// assert!(b"c0ffee".all_in(&HEX));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ByteSet {
+    // The low nibble of a byte selects an entry and its high nibble selects a
+    // bit, split across two halves of 16 entries. Each half can then be used
+    // directly as a byte shuffle table to look up a whole vector of bytes.
+    table: [u8; 32],
+}
+
+// Returns the table index and bit of `byte`.
+#[inline]
+const fn slot(byte: u8) -> (usize, u8) {
+    let index = ((byte >> 7) as usize) << 4 | (byte & 0xF) as usize;
+    (index, 1 << ((byte >> 4) & 7))
+}
+
+impl ByteSet {
+    /// The set containing no bytes.
+    pub const EMPTY: ByteSet = ByteSet { table: [0; 32] };
+
+    /// The set containing every byte.
+    pub const FULL: ByteSet = ByteSet { table: [!0; 32] };
+
+    /// Creates an empty set.
+    #[inline]
+    pub const fn new() -> ByteSet { ByteSet::EMPTY }
+
+    /// Creates a set containing each byte in `bytes`.
+    #[inline]
+    pub const fn from_bytes(bytes: &[u8]) -> ByteSet {
+        let mut set = ByteSet::EMPTY;
+        let mut i = 0;
+        while i < bytes.len() {
+            set = set.with(bytes[i]);
+            i += 1;
+        }
+        set
+    }
+
+    /// Creates a set containing each byte in `range`.
+    #[inline]
+    pub const fn from_range(range: RangeInclusive<u8>) -> ByteSet {
+        ByteSet::EMPTY.with_range(range)
+    }
+
+    /// Returns `self` with `byte` added.
+    #[inline]
+    pub const fn with(self, byte: u8) -> ByteSet {
+        let (index, bit) = slot(byte);
+        let mut table = self.table;
+        table[index] |= bit;
+        ByteSet { table }
+    }
+
+    /// Returns `self` with each byte in `range` added.
+    #[inline]
+    pub const fn with_range(self, range: RangeInclusive<u8>) -> ByteSet {
+        let (start, end) = (*range.start(), *range.end());
+        let mut set = self;
+        let mut byte = start;
+        while byte <= end {
+            set = set.with(byte);
+            if byte == u8::MAX {
+                break;
+            }
+            byte += 1;
+        }
+        set
+    }
+
+    /// Returns the set of bytes in either `self` or `other`.
+    #[inline]
+    pub const fn union(self, other: ByteSet) -> ByteSet {
+        let mut table = self.table;
+        let mut i = 0;
+        while i < table.len() {
+            table[i] |= other.table[i];
+            i += 1;
+        }
+        ByteSet { table }
+    }
+
+    /// Returns the set of bytes not in `self`.
+    #[inline]
+    pub const fn complement(self) -> ByteSet {
+        let mut table = self.table;
+        let mut i = 0;
+        while i < table.len() {
+            table[i] = !table[i];
+            i += 1;
+        }
+        ByteSet { table }
+    }
+
+    /// Returns whether `byte` is in `self`.
+    #[inline]
+    pub const fn contains(&self, byte: u8) -> bool {
+        let (index, bit) = slot(byte);
+        self.table[index] & bit != 0
+    }
+
+    /// Adds `byte` to `self`.
+    #[inline]
+    pub fn insert(&mut self, byte: u8) {
+        *self = self.with(byte);
+    }
+
+    /// Removes `byte` from `self`.
+    #[inline]
+    pub fn remove(&mut self, byte: u8) {
+        let (index, bit) = slot(byte);
+        self.table[index] &= !bit;
+    }
+
+    /// Returns the number of bytes in `self`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.table.iter().map(|b| b.count_ones() as usize).sum()
+    }
+
+    /// Returns whether `self` contains no bytes.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        *self == ByteSet::EMPTY
+    }
+
+    // Returns the lookup table, indexed as described on `table`.
+    #[cfg(feature = "portable_simd")]
+    #[inline]
+    pub(crate) fn table(&self) -> &[u8; 32] {
+        &self.table
+    }
+}
+
+impl fmt::Debug for ByteSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set()
+            .entries((0..=u8::MAX).filter(|&b| self.contains(b)))
+            .finish()
+    }
+}
+
+impl FromIterator<u8> for ByteSet {
+    fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> ByteSet {
+        let mut set = ByteSet::EMPTY;
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<u8> for ByteSet {
+    fn extend<I: IntoIterator<Item = u8>>(&mut self, iter: I) {
+        for byte in iter {
+            self.insert(byte);
+        }
+    }
+}
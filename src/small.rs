@@ -1,142 +1,267 @@
-use core::mem;
-
 use super::*;
 
 // Implementations that may fit within a register
 macro_rules! impl_bytes_small_array {
     ($($n:expr => $s:ident $i:ident,)+) => { $(
-        impl Bytes for [u8; $n] {
+        impl Matches for [u8; $n] {
+            const STRIDE: u32 = 8;
+
+            #[inline]
+            fn eq_bits(self, byte: u8) -> u64 {
+                $i::from_ne_bytes(self).eq_bits(byte)
+            }
+
+            #[inline]
+            fn ne_bits(self, byte: u8) -> u64 {
+                $i::from_ne_bytes(self).ne_bits(byte)
+            }
+
+            #[inline]
+            fn in_bits(self, set: &ByteSet) -> u64 {
+                $i::from_ne_bytes(self).in_bits(set)
+            }
+
+            #[inline]
+            fn range_bits(self, range: ByteRange) -> u64 {
+                $i::from_ne_bytes(self).range_bits(range)
+            }
+
+            #[inline]
+            fn high_bits(self) -> u64 { $i::from_ne_bytes(self).high_bits() }
+        }
+
+        impl MaskBytes for [u8; $n] {
+            type Mask = u8;
+
+            #[inline]
+            fn eq_mask(&self, byte: u8) -> u8 {
+                compact(self.eq_bits(byte)) as u8
+            }
+
+            #[inline]
+            fn ne_mask(&self, byte: u8) -> u8 {
+                compact(self.ne_bits(byte)) as u8
+            }
+        }
+
+        #[cfg(feature = "portable_simd")]
+        impl Bytes for $s {
             #[inline]
             fn is(&self, byte: u8) -> bool {
-                match $n {
-                    // X xor Y equals 0 iff X equals Y
-                    2 => (self[0] ^ byte) | (self[1] ^ byte) == 0,
-                    _ => unsafe {
-                        mem::transmute::<_, $i>(*self).is(byte)
-                    },
-                }
+                self.to_array().is(byte)
             }
 
             #[inline]
             fn is_zero(&self) -> bool {
-                unsafe { mem::transmute::<_, $i>(*self) == 0 }
+                $i::from_ne_bytes(self.to_array()) == 0
             }
 
             #[inline]
             fn contains(&self, byte: u8) -> bool {
-                match $n {
-                    2 => self[0] == byte || self[1] == byte,
-                    _ => unsafe {
-                        mem::transmute::<_, $i>(*self).contains(byte)
-                    },
-                }
+                self.to_array().contains(byte)
             }
 
             #[inline]
             fn first_eq(&self, byte: u8) -> Option<&u8> {
-                unimplemented!()
+                let i = self.position_eq(byte)?;
+                Some(unsafe { byte_at(self, i) })
             }
 
             #[inline]
-            fn first_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
-                unimplemented!()
+            fn first_ne(&self, byte: u8) -> Option<&u8> {
+                let i = self.position_ne(byte)?;
+                Some(unsafe { byte_at(self, i) })
             }
 
             #[inline]
-            fn first_ne(&self, byte: u8) -> Option<&u8> {
-                unimplemented!()
+            fn last_eq(&self, byte: u8) -> Option<&u8> {
+                let i = self.rposition_eq(byte)?;
+                Some(unsafe { byte_at(self, i) })
             }
 
             #[inline]
-            fn first_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
-                unimplemented!()
+            fn last_ne(&self, byte: u8) -> Option<&u8> {
+                let i = self.rposition_ne(byte)?;
+                Some(unsafe { byte_at(self, i) })
             }
 
             #[inline]
-            fn last_eq(&self, byte: u8) -> Option<&u8> {
-                unimplemented!()
+            fn position_eq(&self, byte: u8) -> Option<usize> {
+                first_pos::<Self>(self.eq_bits(byte))
             }
 
             #[inline]
-            fn last_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
-                unimplemented!()
+            fn position_ne(&self, byte: u8) -> Option<usize> {
+                first_pos::<Self>(self.ne_bits(byte))
             }
 
             #[inline]
-            fn last_ne(&self, byte: u8) -> Option<&u8> {
-                unimplemented!()
+            fn rposition_eq(&self, byte: u8) -> Option<usize> {
+                last_pos::<Self>(self.eq_bits(byte))
             }
 
             #[inline]
-            fn last_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
-                unimplemented!()
+            fn rposition_ne(&self, byte: u8) -> Option<usize> {
+                last_pos::<Self>(self.ne_bits(byte))
             }
-        }
 
-        impl SizedBytes for [u8; $n] {
             #[inline]
-            fn splat(byte: u8) -> Self { [byte; $n] }
-        }
+            fn first_any2(&self, a: u8, b: u8) -> Option<&u8> {
+                let mask = self.eq_bits(a) | self.eq_bits(b);
+                let i = first_pos::<Self>(mask)?;
+                Some(unsafe { byte_at(self, i) })
+            }
 
-        #[cfg(feature = "simd")]
-        impl Bytes for $s {
             #[inline]
-            fn is(&self, byte: u8) -> bool {
-                unsafe { mem::transmute::<_, [u8; $n]>(*self).is(byte) }
+            fn first_any3(&self, a: u8, b: u8, c: u8) -> Option<&u8> {
+                let mask = self.eq_bits(a) | self.eq_bits(b) | self.eq_bits(c);
+                let i = first_pos::<Self>(mask)?;
+                Some(unsafe { byte_at(self, i) })
             }
 
             #[inline]
-            fn is_zero(&self) -> bool {
-                unsafe { mem::transmute::<_, $i>(*self) == 0 }
+            fn first_in(&self, set: &ByteSet) -> Option<&u8> {
+                let i = first_pos::<Self>(self.in_bits(set))?;
+                Some(unsafe { byte_at(self, i) })
             }
 
             #[inline]
-            fn contains(&self, byte: u8) -> bool {
-                unsafe { mem::transmute::<_, [u8; $n]>(*self).contains(byte) }
+            fn first_non_ascii(&self) -> Option<&u8> {
+                let i = first_pos::<Self>(self.high_bits())?;
+                Some(unsafe { byte_at(self, i) })
             }
 
             #[inline]
-            fn first_eq(&self, byte: u8) -> Option<&u8> {
-                unimplemented!()
+            fn all_in_range(&self, range: RangeInclusive<u8>) -> bool {
+                all_in_range(range, |r| self.range_bits(r) != 0)
             }
 
             #[inline]
-            fn first_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
-                unimplemented!()
+            fn first_in_range(&self, range: RangeInclusive<u8>) -> Option<&u8> {
+                let mask = self.range_bits(ByteRange::new(range)?);
+                let i = first_pos::<Self>(mask)?;
+                Some(unsafe { byte_at(self, i) })
             }
 
             #[inline]
-            fn first_ne(&self, byte: u8) -> Option<&u8> {
-                unimplemented!()
+            fn count_eq(&self, byte: u8) -> usize {
+                self.eq_bits(byte).count_ones() as usize
             }
 
             #[inline]
-            fn first_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
-                unimplemented!()
+            fn count_ne(&self, byte: u8) -> usize {
+                self.ne_bits(byte).count_ones() as usize
             }
 
             #[inline]
-            fn last_eq(&self, byte: u8) -> Option<&u8> {
-                unimplemented!()
+            fn iter_eq(&self, byte: u8) -> Positions<'_> {
+                Positions::new(unsafe { as_bytes(self) }, byte, true)
             }
 
             #[inline]
-            fn last_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
-                unimplemented!()
+            fn iter_ne(&self, byte: u8) -> Positions<'_> {
+                Positions::new(unsafe { as_bytes(self) }, byte, false)
             }
 
             #[inline]
-            fn last_ne(&self, byte: u8) -> Option<&u8> {
-                unimplemented!()
+            fn find(&self, needle: &[u8]) -> Option<usize> {
+                search::find(unsafe { as_bytes(self) }, needle)
+            }
+
+            #[inline]
+            fn rfind(&self, needle: &[u8]) -> Option<usize> {
+                search::rfind(unsafe { as_bytes(self) }, needle)
+            }
+        }
+
+        #[cfg(feature = "portable_simd")]
+        impl BytesMut for $s {
+            #[inline]
+            fn first_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
+                let i = self.position_eq(byte)?;
+                Some(unsafe { byte_at_mut(self, i) })
+            }
+
+            #[inline]
+            fn first_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
+                let i = self.position_ne(byte)?;
+                Some(unsafe { byte_at_mut(self, i) })
+            }
+
+            #[inline]
+            fn last_eq_mut(&mut self, byte: u8) -> Option<&mut u8> {
+                let i = self.rposition_eq(byte)?;
+                Some(unsafe { byte_at_mut(self, i) })
             }
 
             #[inline]
             fn last_ne_mut(&mut self, byte: u8) -> Option<&mut u8> {
-                unimplemented!()
+                let i = self.rposition_ne(byte)?;
+                Some(unsafe { byte_at_mut(self, i) })
+            }
+
+            #[inline]
+            fn fill(&mut self, byte: u8) { *self = Self::splat(byte) }
+
+            #[inline]
+            fn replace(&mut self, from: u8, to: u8) {
+                let mut arr = self.to_array();
+                arr.replace(from, to);
+                *self = Self::from_array(arr);
+            }
+
+            #[inline]
+            fn replace_all_in(&mut self, set: &ByteSet, to: u8) {
+                let mut arr = self.to_array();
+                arr.replace_all_in(set, to);
+                *self = Self::from_array(arr);
+            }
+        }
+
+        #[cfg(feature = "portable_simd")]
+        impl Matches for $s {
+            const STRIDE: u32 = 8;
+
+            #[inline]
+            fn eq_bits(self, byte: u8) -> u64 {
+                self.to_array().eq_bits(byte)
+            }
+
+            #[inline]
+            fn ne_bits(self, byte: u8) -> u64 {
+                self.to_array().ne_bits(byte)
+            }
+
+            #[inline]
+            fn in_bits(self, set: &ByteSet) -> u64 {
+                self.to_array().in_bits(set)
+            }
+
+            #[inline]
+            fn range_bits(self, range: ByteRange) -> u64 {
+                self.to_array().range_bits(range)
+            }
+
+            #[inline]
+            fn high_bits(self) -> u64 { self.to_array().high_bits() }
+        }
+
+        #[cfg(feature = "portable_simd")]
+        impl MaskBytes for $s {
+            type Mask = u8;
+
+            #[inline]
+            fn eq_mask(&self, byte: u8) -> u8 {
+                compact(self.eq_bits(byte)) as u8
+            }
+
+            #[inline]
+            fn ne_mask(&self, byte: u8) -> u8 {
+                compact(self.ne_bits(byte)) as u8
             }
         }
 
-        #[cfg(feature = "simd")]
+        #[cfg(feature = "portable_simd")]
         impl SizedBytes for $s {
             #[inline]
             fn splat(byte: u8) -> Self { Self::splat(byte) }
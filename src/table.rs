@@ -0,0 +1,233 @@
+#[cfg(not(feature = "portable_simd"))]
+use core::convert::TryFrom;
+
+#[cfg(feature = "portable_simd")]
+use core::{array, mem, ptr};
+
+use super::*;
+
+/// A mapping of every byte to another, for translating the bytes of a slice
+/// through a 256-entry lookup table.
+///
+/// Tables can be built in constant expressions:
+///
+/// ```
+/// use byte_ops::{ByteSet, ByteTable};
+///
+/// // Turns each delimiter into a space and each non-ASCII byte into `?`
+/// const NORMALIZE: ByteTable = ByteTable::IDENTITY
+///     .with_set(&ByteSet::from_range(0x80..=0xFF), b'?')
+///     .with_set(&ByteSet::from_bytes(b",;\t"), b' ');
+///
+/// let mut text = *b"a,b;\xC3\xA9\tc";
+/// NORMALIZE.translate(&mut text);
+/// assert_eq!(&text, b"a b ?? c");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ByteTable {
+    table: [u8; 256],
+}
+
+impl ByteTable {
+    /// The table that maps every byte to itself.
+    pub const IDENTITY: ByteTable = ByteTable::identity();
+
+    /// Creates a table that maps each byte `b` to `table[b]`.
+    #[inline]
+    pub const fn new(table: [u8; 256]) -> ByteTable { ByteTable { table } }
+
+    const fn identity() -> ByteTable {
+        let mut table = [0; 256];
+        let mut i = 0;
+        while i < table.len() {
+            table[i] = i as u8;
+            i += 1;
+        }
+        ByteTable { table }
+    }
+
+    /// Returns `self` with `from` mapped to `to`.
+    #[inline]
+    pub const fn with(self, from: u8, to: u8) -> ByteTable {
+        let mut table = self.table;
+        table[from as usize] = to;
+        ByteTable { table }
+    }
+
+    /// Returns `self` with each byte in `set` mapped to `to`.
+    #[inline]
+    pub const fn with_set(self, set: &ByteSet, to: u8) -> ByteTable {
+        let mut table = self.table;
+        let mut i = 0;
+        while i < table.len() {
+            if set.contains(i as u8) {
+                table[i] = to;
+            }
+            i += 1;
+        }
+        ByteTable { table }
+    }
+
+    /// Returns the byte that `byte` maps to.
+    #[inline]
+    pub const fn get(&self, byte: u8) -> u8 { self.table[byte as usize] }
+
+    /// Returns the underlying lookup table.
+    #[inline]
+    pub const fn as_array(&self) -> &[u8; 256] { &self.table }
+
+    /// Replaces each byte in `bytes` with the byte it maps to.
+    #[inline]
+    pub fn translate(&self, bytes: &mut [u8]) { translate(bytes, &self.table) }
+
+    /// Writes the byte that each byte in `src` maps to into `dst`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `src` and `dst` have different lengths.
+    #[inline]
+    pub fn translate_into(&self, src: &[u8], dst: &mut [u8]) {
+        translate_into(src, dst, &self.table)
+    }
+}
+
+impl Default for ByteTable {
+    #[inline]
+    fn default() -> ByteTable { ByteTable::IDENTITY }
+}
+
+impl From<[u8; 256]> for ByteTable {
+    #[inline]
+    fn from(table: [u8; 256]) -> ByteTable { ByteTable { table } }
+}
+
+impl AsRef<[u8; 256]> for ByteTable {
+    #[inline]
+    fn as_ref(&self) -> &[u8; 256] { &self.table }
+}
+
+/// Replaces each byte `b` in `bytes` with `table[b]`.
+///
+/// ```
+/// let mut table = [0; 256];
+/// for (i, x) in table.iter_mut().enumerate() {
+///     *x = (i as u8).to_ascii_uppercase();
+/// }
+///
+/// let mut text = *b"Hello, world";
+/// byte_ops::translate(&mut text, &table);
+/// assert_eq!(&text, b"HELLO, WORLD");
+/// ```
+#[inline]
+pub fn translate(bytes: &mut [u8], table: &[u8; 256]) {
+    imp::translate(bytes, table)
+}
+
+/// Writes `table[b]` for each byte `b` in `src` into the same position of
+/// `dst`.
+///
+/// # Panics
+///
+/// Panics if `src` and `dst` have different lengths.
+#[inline]
+pub fn translate_into(src: &[u8], dst: &mut [u8], table: &[u8; 256]) {
+    assert_eq!(src.len(), dst.len(),
+               "source and destination slices have different lengths");
+    imp::translate_into(src, dst, table)
+}
+
+// Without vectors, table lookups can't be batched, so they're unrolled
+#[cfg(not(feature = "portable_simd"))]
+mod imp {
+    use super::*;
+
+    // Looks up eight bytes at once, leaving the compiler free to interleave
+    // the independent loads
+    #[inline(always)]
+    fn lookup8(c: &[u8], table: &[u8; 256]) -> [u8; 8] {
+        let x = <[u8; 8]>::try_from(c).unwrap();
+        let t = |i: usize| table[x[i] as usize];
+        [t(0), t(1), t(2), t(3), t(4), t(5), t(6), t(7)]
+    }
+
+    #[inline]
+    pub fn translate(bytes: &mut [u8], table: &[u8; 256]) {
+        let mut chunks = bytes.chunks_exact_mut(8);
+        for c in &mut chunks {
+            let x = lookup8(c, table);
+            c.copy_from_slice(&x);
+        }
+        for b in chunks.into_remainder() {
+            *b = table[*b as usize];
+        }
+    }
+
+    #[inline]
+    pub fn translate_into(src: &[u8], dst: &mut [u8], table: &[u8; 256]) {
+        let mut a = src.chunks_exact(8);
+        let mut b = dst.chunks_exact_mut(8);
+        for (x, y) in a.by_ref().zip(b.by_ref()) {
+            y.copy_from_slice(&lookup8(x, table));
+        }
+        for (x, y) in a.remainder().iter().zip(b.into_remainder()) {
+            *y = table[*x as usize];
+        }
+    }
+}
+
+// Splits the table into 16 rows by the high nibble of each byte, so that a
+// row is a byte shuffle indexed by the low nibble
+#[cfg(feature = "portable_simd")]
+mod imp {
+    use super::*;
+
+    const SIZE: usize = mem::size_of::<Batch>();
+
+    // Each row repeated across the whole batch, since a shuffle index only
+    // reaches lanes within its own 16
+    #[inline]
+    fn rows(table: &[u8; 256]) -> [Batch; 16] {
+        array::from_fn(|h| {
+            Batch::from_array(array::from_fn(|i| table[h * 16 + i % 16]))
+        })
+    }
+
+    // Shuffles every row by the low nibbles and keeps each lane from the row
+    // selected by its high nibble
+    #[inline]
+    fn lookup(x: Batch, rows: &[Batch; 16]) -> Batch {
+        let nibbles = x & Batch::splat(0xF);
+        let high = x >> 4;
+        rows.iter().enumerate().fold(Batch::splat(0), |acc, (h, row)| {
+            let m = high.simd_eq(Batch::splat(h as u8));
+            m.select(row.swizzle_dyn(nibbles), acc)
+        })
+    }
+
+    #[inline]
+    pub fn translate(bytes: &mut [u8], table: &[u8; 256]) {
+        let rows = rows(table);
+        rewrite(bytes, |x| lookup(x, &rows), |b| table[b as usize]);
+    }
+
+    // The two slices are rarely aligned the same way, so batches are read
+    // and written unaligned
+    #[inline]
+    pub fn translate_into(src: &[u8], dst: &mut [u8], table: &[u8; 256]) {
+        let rows = rows(table);
+
+        let mut a = src.chunks_exact(SIZE);
+        let mut b = dst.chunks_exact_mut(SIZE);
+        for (x, y) in a.by_ref().zip(b.by_ref()) {
+            unsafe {
+                let x = ptr::read_unaligned(x.as_ptr() as *const Batch);
+                let y = y.as_mut_ptr() as *mut Batch;
+                ptr::write_unaligned(y, lookup(x, &rows));
+            }
+        }
+
+        for (x, y) in a.remainder().iter().zip(b.into_remainder()) {
+            *y = table[*x as usize];
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! UTF-8 validation that skips over ASCII a whole batch at a time.
+
+use core::{fmt, str};
+
+use super::*;
+
+/// An error from [`validate_utf8`](fn.validate_utf8.html), with the same
+/// meaning as [`core::str::Utf8Error`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Utf8Error {
+    valid_up_to: usize,
+    error_len: Option<u8>,
+}
+
+impl Utf8Error {
+    /// Returns the length of the valid UTF-8 prefix of the input.
+    #[inline]
+    pub fn valid_up_to(&self) -> usize { self.valid_up_to }
+
+    /// Returns the length of the invalid sequence after the valid prefix, or
+    /// `None` if the input ends partway through a sequence that could still
+    /// be completed.
+    #[inline]
+    pub fn error_len(&self) -> Option<usize> {
+        self.error_len.map(|len| len as usize)
+    }
+}
+
+impl fmt::Display for Utf8Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.error_len {
+            Some(len) => write!(f, "invalid utf-8 sequence of {} bytes from \
+                                    index {}", len, self.valid_up_to),
+            None => write!(f, "incomplete utf-8 byte sequence from index {}",
+                           self.valid_up_to),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Utf8Error {}
+
+// The width of the sequence started by a lead byte, along with the range of
+// its second byte, which is narrower than `0x80..=0xBF` after some leads to
+// rule out overlong encodings, surrogates and code points past `U+10FFFF`.
+#[derive(Clone, Copy)]
+struct Lead {
+    width: u8,
+    lo: u8,
+    hi: u8,
+}
+
+const INVALID: Lead = Lead { width: 0, lo: 0, hi: 0 };
+
+// The `Lead` of every byte, with a `width` of 0 for those that can't start a
+// multibyte sequence.
+static LEADS: [Lead; 256] = leads();
+
+const fn leads() -> [Lead; 256] {
+    let mut table = [INVALID; 256];
+    let mut b = 0xC2;
+    while b <= 0xF4 {
+        table[b] = match b {
+            0xC2..=0xDF => Lead { width: 2, lo: 0x80, hi: 0xBF },
+            0xE0        => Lead { width: 3, lo: 0xA0, hi: 0xBF },
+            0xED        => Lead { width: 3, lo: 0x80, hi: 0x9F },
+            0xE1..=0xEF => Lead { width: 3, lo: 0x80, hi: 0xBF },
+            0xF0        => Lead { width: 4, lo: 0x90, hi: 0xBF },
+            0xF4        => Lead { width: 4, lo: 0x80, hi: 0x8F },
+            _           => Lead { width: 4, lo: 0x80, hi: 0xBF },
+        };
+        b += 1;
+    }
+    table
+}
+
+// Returns the length of the multibyte sequence at the start of `b`, or the
+// `error_len` of a `Utf8Error` there.
+#[inline]
+fn sequence(b: &[u8]) -> Result<usize, Option<u8>> {
+    let lead = LEADS[b[0] as usize];
+    if lead.width == 0 {
+        return Err(Some(1));
+    }
+
+    // Each byte is checked before the input may end, so an incomplete
+    // sequence is only reported if what there is of it is valid
+    let width = lead.width as usize;
+    for i in 1..width {
+        let (lo, hi) = if i == 1 { (lead.lo, lead.hi) } else { (0x80, 0xBF) };
+        match b.get(i) {
+            None => return Err(None),
+            Some(&x) if x < lo || x > hi => return Err(Some(i as u8)),
+            Some(_) => {},
+        }
+    }
+    Ok(width)
+}
+
+/// Returns `bytes` as a string slice if it is valid UTF-8, like
+/// [`core::str::from_utf8`].
+///
+/// Runs of ASCII are skipped a whole batch at a time, so this is fastest on
+/// mostly-ASCII text.
+///
+/// ```
+/// use byte_ops::utf8::validate_utf8;
+///
+/// assert_eq!(validate_utf8("señor".as_bytes()), Ok("señor"));
+///
+/// let err = validate_utf8(b"se\xC3\x28or").unwrap_err();
+/// assert_eq!(err.valid_up_to(), 2);
+/// assert_eq!(err.error_len(), Some(1));
+///
+/// let err = validate_utf8(b"se\xC3").unwrap_err();
+/// assert_eq!(err.error_len(), None);
+/// ```
+pub fn validate_utf8(bytes: &[u8]) -> Result<&str, Utf8Error> {
+    let mut i = 0;
+    loop {
+        let rest = &bytes[i..];
+        match first_index(rest, |x| x >= 0x80, |x| x.high_bits()) {
+            Some(j) => i += j,
+            None => return Ok(unsafe { str::from_utf8_unchecked(bytes) }),
+        }
+
+        // Multibyte characters tend to come in runs, so only go back to
+        // skipping batches once there's ASCII again
+        while i < bytes.len() && bytes[i] >= 0x80 {
+            match sequence(&bytes[i..]) {
+                Ok(width) => i += width,
+                Err(error_len) => {
+                    return Err(Utf8Error { valid_up_to: i, error_len });
+                },
+            }
+        }
+    }
+}
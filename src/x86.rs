@@ -0,0 +1,334 @@
+// Slice kernels using x86 vector instructions, selected at runtime.
+//
+// Unlike the `portable_simd` feature, these work on stable Rust and don't
+// require building for a specific CPU: the best instruction set available is
+// detected once and its kernels are then called through function pointers.
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+// The slice operations implemented with a specific instruction set.
+pub struct Kernels {
+    // Returns the index of the first byte that is (`true`) or is not
+    // (`false`) equal to a byte.
+    pub position: unsafe fn(&[u8], u8, bool) -> Option<usize>,
+
+    // Returns the index of the last byte that is (`true`) or is not (`false`)
+    // equal to a byte.
+    pub rposition: unsafe fn(&[u8], u8, bool) -> Option<usize>,
+
+    // Returns the number of bytes equal to a byte.
+    pub count: unsafe fn(&[u8], u8) -> usize,
+
+    // Returns the index of the first byte equal to either of two bytes.
+    pub position_any2: unsafe fn(&[u8], u8, u8) -> Option<usize>,
+
+    // Returns the index of the first byte equal to any of three bytes.
+    pub position_any3: unsafe fn(&[u8], u8, u8, u8) -> Option<usize>,
+
+    // Returns the index of the first byte in a `ByteRange`, given by its `lo`
+    // and `span`.
+    pub position_range: unsafe fn(&[u8], u8, u8) -> Option<usize>,
+}
+
+// An instruction set with its own kernels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    Sse2,
+    Avx2,
+    Avx512bw,
+}
+
+impl Backend {
+    // Every backend, from least to most preferred.
+    pub const ALL: &'static [Backend] = &[
+        Backend::Sse2,
+        Backend::Avx2,
+        Backend::Avx512bw,
+    ];
+
+    // Returns the name of `self`, as used by `BYTE_OPS_BACKEND`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Backend::Sse2     => "sse2",
+            Backend::Avx2     => "avx2",
+            Backend::Avx512bw => "avx512bw",
+        }
+    }
+
+    // Returns whether the current CPU supports `self`.
+    pub fn is_available(self) -> bool {
+        match self {
+            Backend::Sse2 => is_x86_feature_detected!("sse2"),
+            Backend::Avx2 => is_x86_feature_detected!("avx2"),
+            Backend::Avx512bw => is_x86_feature_detected!("avx512bw"),
+        }
+    }
+
+    // Returns the kernels of `self`, which may only be called if
+    // `is_available` returns `true`.
+    pub fn kernels(self) -> &'static Kernels {
+        match self {
+            Backend::Sse2 => &sse2::KERNELS,
+            Backend::Avx2 => &avx2::KERNELS,
+            Backend::Avx512bw => &avx512bw::KERNELS,
+        }
+    }
+}
+
+const UNDETECTED: usize = usize::MAX;
+
+// Returns the most preferred backend available, if any.
+//
+// For testing, the `BYTE_OPS_BACKEND` environment variable forces a backend by
+// name, or the portable code with "none". This lets each backend be verified
+// on one machine, including under an emulator such as Intel SDE.
+#[inline]
+pub fn selected() -> Option<Backend> {
+    // The index of the selected backend within `Backend::ALL`
+    static SELECTED: AtomicUsize = AtomicUsize::new(UNDETECTED);
+
+    let mut selected = SELECTED.load(Ordering::Relaxed);
+    if selected == UNDETECTED {
+        selected = select();
+        SELECTED.store(selected, Ordering::Relaxed);
+    }
+    Backend::ALL.get(selected).cloned()
+}
+
+// Any name other than an available backend or "none" is ignored in favor of
+// the detected backend, since a stray variable must not break library code.
+#[cold]
+fn select() -> usize {
+    let all = Backend::ALL;
+    let detected = || {
+        all.iter().rposition(|b| b.is_available()).unwrap_or(all.len())
+    };
+    let name = match ::std::env::var("BYTE_OPS_BACKEND") {
+        Ok(name) => name,
+        Err(_) => return detected(),
+    };
+    if name == "none" {
+        return all.len();
+    }
+    match all.iter().position(|b| b.name() == name) {
+        Some(i) if all[i].is_available() => i,
+        _ => detected(),
+    }
+}
+
+// Returns the kernels of the selected backend, if any.
+#[inline]
+pub fn kernels() -> Option<&'static Kernels> {
+    selected().map(Backend::kernels)
+}
+
+// Evaluates to the index of the first byte of `$b` whose bit is set in the
+// mask that `$mask` gives for the `$lanes` bytes at `$ptr`, where `$b` is at
+// least `$lanes` long.
+macro_rules! position_by {
+    ($b:expr, $lanes:expr, |$ptr:ident| $mask:expr) => {{
+        let (start, len) = ($b.as_ptr(), $b.len());
+
+        let mut i = 0;
+        let mut found = None;
+        while i + $lanes <= len {
+            let $ptr = start.add(i);
+            let m = $mask;
+            if m != 0 {
+                found = Some(i + m.trailing_zeros() as usize);
+                break;
+            }
+            i += $lanes;
+        }
+
+        if found.is_none() && i < len {
+            // Reread the last vector, dropping the bytes already seen
+            let $ptr = start.add(len - $lanes);
+            let m = $mask >> ($lanes - (len - i));
+            if m != 0 {
+                found = Some(i + m.trailing_zeros() as usize);
+            }
+        }
+        found
+    }};
+}
+
+macro_rules! kernels {
+    (
+        $name:ident, $feature:tt, $vec:ty, $lanes:expr,
+        $loadu:ident, $set1:ident, $sub:ident,
+        |$v:ident, $needle:ident| $mask:expr,
+        |$w:ident, $span:ident| $le_mask:expr
+    ) => {
+        mod $name {
+            use super::*;
+
+            pub static KERNELS: Kernels = Kernels {
+                position,
+                rposition,
+                count,
+                position_any2,
+                position_any3,
+                position_range,
+            };
+
+            const LANES: usize = $lanes;
+
+            // Every mask bit of a vector.
+            const FULL: u64 = !0 >> (64 - LANES);
+
+            // Returns the mask of the `LANES` bytes at `ptr` that equal
+            // `needle`, with the bits in `flip` inverted.
+            #[inline]
+            #[target_feature(enable = $feature)]
+            unsafe fn mask(ptr: *const u8, $needle: $vec, flip: u64) -> u64 {
+                let $v = $loadu(ptr as *const $vec);
+                $mask ^ flip
+            }
+
+            // Returns the mask of the `LANES` bytes at `ptr` that are at most
+            // `span` above `lo`, which wraps like `ByteRange`.
+            #[inline]
+            #[target_feature(enable = $feature)]
+            unsafe fn range_mask(ptr: *const u8, lo: $vec, $span: $vec) -> u64 {
+                let $w = $sub($loadu(ptr as *const $vec), lo);
+                $le_mask
+            }
+
+            #[target_feature(enable = $feature)]
+            unsafe fn position(b: &[u8], byte: u8, eq: bool) -> Option<usize> {
+                if b.len() < LANES {
+                    return b.iter().position(|&x| (x == byte) == eq);
+                }
+
+                let needle = $set1(byte as i8);
+                let flip = if eq { 0 } else { FULL };
+                position_by!(b, LANES, |p| mask(p, needle, flip))
+            }
+
+            #[target_feature(enable = $feature)]
+            unsafe fn position_any2(b: &[u8], x: u8, y: u8) -> Option<usize> {
+                if b.len() < LANES {
+                    return b.iter().position(|&c| c == x || c == y);
+                }
+
+                let (x, y) = ($set1(x as i8), $set1(y as i8));
+                position_by!(b, LANES, |p| mask(p, x, 0) | mask(p, y, 0))
+            }
+
+            #[target_feature(enable = $feature)]
+            unsafe fn position_any3(b: &[u8], x: u8, y: u8, z: u8)
+                -> Option<usize>
+            {
+                if b.len() < LANES {
+                    return b.iter().position(|&c| c == x || c == y || c == z);
+                }
+
+                let (x, y) = ($set1(x as i8), $set1(y as i8));
+                let z = $set1(z as i8);
+                position_by!(b, LANES, |p| {
+                    mask(p, x, 0) | mask(p, y, 0) | mask(p, z, 0)
+                })
+            }
+
+            #[target_feature(enable = $feature)]
+            unsafe fn position_range(b: &[u8], lo: u8, span: u8)
+                -> Option<usize>
+            {
+                if b.len() < LANES {
+                    return b.iter().position(|&x| x.wrapping_sub(lo) <= span);
+                }
+
+                let (lo, span) = ($set1(lo as i8), $set1(span as i8));
+                position_by!(b, LANES, |p| range_mask(p, lo, span))
+            }
+
+            #[target_feature(enable = $feature)]
+            unsafe fn rposition(b: &[u8], byte: u8, eq: bool) -> Option<usize> {
+                if b.len() < LANES {
+                    return b.iter().rposition(|&x| (x == byte) == eq);
+                }
+
+                let ptr = b.as_ptr();
+                let needle = $set1(byte as i8);
+                let flip = if eq { 0 } else { FULL };
+
+                let mut end = b.len();
+                while end >= LANES {
+                    let i = end - LANES;
+                    let m = mask(ptr.add(i), needle, flip);
+                    if m != 0 {
+                        return Some(i + 63 - m.leading_zeros() as usize);
+                    }
+                    end = i;
+                }
+
+                if end > 0 {
+                    // Reread the first vector, keeping the bytes not yet seen
+                    let m = mask(ptr, needle, flip) & !(!0 << end);
+                    if m != 0 {
+                        return Some(63 - m.leading_zeros() as usize);
+                    }
+                }
+                None
+            }
+
+            #[target_feature(enable = $feature)]
+            unsafe fn count(b: &[u8], byte: u8) -> usize {
+                if b.len() < LANES {
+                    return b.iter().filter(|&&x| x == byte).count();
+                }
+
+                let (ptr, len) = (b.as_ptr(), b.len());
+                let needle = $set1(byte as i8);
+
+                let mut count = 0;
+                let mut i = 0;
+                while i + LANES <= len {
+                    count += mask(ptr.add(i), needle, 0).count_ones() as usize;
+                    i += LANES;
+                }
+
+                if i < len {
+                    let m = mask(ptr.add(len - LANES), needle, 0);
+                    count += (m >> (LANES - (len - i))).count_ones() as usize;
+                }
+                count
+            }
+        }
+    };
+}
+
+// Without unsigned byte comparisons, a byte is at most `span` exactly when
+// the unsigned minimum of the two is the byte itself.
+kernels! {
+    sse2, "sse2", __m128i, 16, _mm_loadu_si128, _mm_set1_epi8, _mm_sub_epi8,
+    |v, needle| _mm_movemask_epi8(_mm_cmpeq_epi8(v, needle)) as u16 as u64,
+    |w, span| {
+        let le = _mm_cmpeq_epi8(_mm_min_epu8(w, span), w);
+        _mm_movemask_epi8(le) as u16 as u64
+    }
+}
+
+kernels! {
+    avx2, "avx2", __m256i, 32, _mm256_loadu_si256, _mm256_set1_epi8,
+    _mm256_sub_epi8,
+    |v, needle| _mm256_movemask_epi8(_mm256_cmpeq_epi8(v, needle)) as u32 as u64,
+    |w, span| {
+        let le = _mm256_cmpeq_epi8(_mm256_min_epu8(w, span), w);
+        _mm256_movemask_epi8(le) as u32 as u64
+    }
+}
+
+// Compares straight into a mask register rather than a vector.
+kernels! {
+    avx512bw, "avx512bw", __m512i, 64, _mm512_loadu_si512, _mm512_set1_epi8,
+    _mm512_sub_epi8,
+    |v, needle| _mm512_cmpeq_epi8_mask(v, needle),
+    |w, span| _mm512_cmple_epu8_mask(w, span)
+}